@@ -0,0 +1,112 @@
+use std::{fs, path::Path};
+
+use clap::Parser;
+use criterion::{criterion_group, criterion_main, Criterion};
+use dfu_crawler::{post_process, read_from_local, Arguments};
+use serde_json::json;
+
+const CATEGORY_COUNT: i32 = 10;
+const ARTICLES_PER_CATEGORY: i32 = 100;
+
+fn titles(text: &str) -> serde_json::Value {
+    json!({ "KR": format!("{} KR", text), "EN": format!("{} EN", text), "CN": format!("{} CN", text) })
+}
+
+// Lays out a crawled_data directory shaped like a real crawl: one category tree plus one JSON per article
+fn write_fixtures(root: &Path) -> anyhow::Result<()> {
+    let category_dir = root.join("crawled_data").join("category");
+    let articles_dir = root.join("crawled_data").join("articles");
+
+    fs::create_dir_all(&category_dir)?;
+    fs::create_dir_all(&articles_dir)?;
+
+    let mut categories = vec![];
+
+    for category_id in 1..=CATEGORY_COUNT {
+        let category_title = format!("Category {}", category_id);
+        let mut children = vec![];
+
+        for index in 0..ARTICLES_PER_CATEGORY {
+            let id = category_id * 10_000 + index;
+            let title = format!("Article {}", id);
+
+            children.push(json!({
+                "id": id,
+                "parent_id": category_id,
+                "position": index,
+                "type": "ARTICLE",
+                "status": "PUBLISHED",
+                "titles": titles(&title),
+                "children": [],
+                "modified": false,
+            }));
+
+            let article = json!({
+                "code": "0",
+                "message": "OK",
+                "data": {
+                    "id": id,
+                    "category_id": category_id,
+                    "category_titles": titles(&category_title),
+                    "status": "PUBLISHED",
+                    "titles": titles(&title),
+                    "subtitles": titles("Subtitle"),
+                    "image_url": null,
+                    "attachments": {},
+                    "contents": titles(&"던전앤파이터 유니버스 본문 ".repeat(200)),
+                },
+            });
+
+            fs::write(
+                articles_dir.join(format!("{}.json", id)),
+                article.to_string(),
+            )?;
+        }
+
+        categories.push(json!({
+            "id": category_id,
+            "parent_id": null,
+            "position": category_id,
+            "type": "CATEGORY",
+            "status": "PUBLISHED",
+            "titles": titles(&category_title),
+            "children": children,
+            "modified": false,
+        }));
+    }
+
+    let response = json!({ "code": "0", "message": "OK", "data": categories });
+    fs::write(category_dir.join("categories.json"), response.to_string())?;
+
+    Ok(())
+}
+
+fn bench_pipeline(c: &mut Criterion) {
+    let root = std::env::temp_dir().join("dfu_crawler_bench");
+    let _ = fs::remove_dir_all(&root);
+
+    write_fixtures(&root).unwrap();
+
+    // read_from_local and post_process work relative to the current directory
+    std::env::set_current_dir(&root).unwrap();
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let args = Arguments::parse_from(["dfu_crawler", "--use-local"]);
+    let ignored_ids = Default::default();
+
+    c.bench_function("read_from_local + post_process (1000 articles)", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let data = read_from_local(&args).await.unwrap();
+                post_process(&args, &data, &[], &ignored_ids).await.unwrap();
+            })
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_pipeline
+}
+criterion_main!(benches);
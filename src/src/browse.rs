@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{
+        Block, Borders, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState,
+    },
+    DefaultTerminal, Frame,
+};
+use unicode_width::UnicodeWidthChar;
+
+use crate::{localized, Arguments, ArticleDataResponse, CategoryChildResponse, CrawlData};
+
+struct TreeEntry {
+    depth: usize,
+    title: String,
+    article_id: Option<i32>,
+}
+
+struct Browser<'a> {
+    args: &'a Arguments,
+    entries: Vec<TreeEntry>,
+    articles: HashMap<i32, &'a ArticleDataResponse>,
+    list_state: ListState,
+    search_input: Option<String>,
+    last_search: String,
+    scroll: usize,
+    scrolled_entry: Option<usize>,
+    page_height: usize,
+}
+
+pub fn run(args: &Arguments, data: &CrawlData) -> anyhow::Result<()> {
+    let mut browser = Browser::new(args, data);
+
+    let mut terminal = ratatui::init();
+    let result = browser.run(&mut terminal);
+    ratatui::restore();
+
+    result
+}
+
+// The cached archive is read as it is, so a title or contents may be missing in every language
+fn or_placeholder(text: &str, placeholder: &str) -> String {
+    match text.is_empty() {
+        true => placeholder.to_string(),
+        false => text.to_string(),
+    }
+}
+
+fn flatten_tree(
+    args: &Arguments,
+    children: &[CategoryChildResponse],
+    depth: usize,
+    entries: &mut Vec<TreeEntry>,
+) {
+    for child in children {
+        entries.push(TreeEntry {
+            depth,
+            title: or_placeholder(localized(&child.titles, args.title_lang()), "(untitled)"),
+            article_id: (child.type_ == "ARTICLE").then_some(child.id),
+        });
+
+        flatten_tree(args, &child.children, depth + 1, entries);
+    }
+}
+
+impl<'a> Browser<'a> {
+    fn new(args: &'a Arguments, data: &'a CrawlData) -> Self {
+        let mut entries = vec![];
+        flatten_tree(args, &data.categories, 0, &mut entries);
+
+        let articles = data
+            .ko_articles
+            .iter()
+            .map(|article| (article.id, article))
+            .collect();
+
+        let mut list_state = ListState::default();
+        if !entries.is_empty() {
+            list_state.select(Some(0));
+        }
+
+        Self {
+            args,
+            entries,
+            articles,
+            list_state,
+            search_input: None,
+            last_search: String::new(),
+            scroll: 0,
+            scrolled_entry: None,
+            page_height: 1,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> anyhow::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            if let Some(input) = self.search_input.as_mut() {
+                match key.code {
+                    KeyCode::Char(c) => input.push(c),
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Enter => {
+                        self.last_search = self.search_input.take().unwrap_or_default();
+                        self.search_next();
+                    }
+                    KeyCode::Esc => self.search_input = None,
+                    _ => {}
+                }
+
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Up | KeyCode::Char('k') => self.list_state.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') => self.list_state.select_next(),
+                KeyCode::Home => self.list_state.select_first(),
+                KeyCode::End => self.list_state.select_last(),
+                KeyCode::Char('/') => self.search_input = Some(String::new()),
+                KeyCode::Char('n') => self.search_next(),
+                KeyCode::PageDown | KeyCode::Char(' ') => {
+                    self.scroll += self.page_height.saturating_sub(1).max(1);
+                }
+                KeyCode::PageUp => {
+                    self.scroll = self
+                        .scroll
+                        .saturating_sub(self.page_height.saturating_sub(1).max(1));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn search_next(&mut self) {
+        if self.last_search.is_empty() || self.entries.is_empty() {
+            return;
+        }
+
+        let query = self.last_search.to_lowercase();
+        let start = self.list_state.selected().map_or(0, |i| i + 1);
+        let len = self.entries.len();
+
+        let found = (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|&i| self.entries[i].title.to_lowercase().contains(&query));
+
+        if found.is_some() {
+            self.list_state.select(found);
+        }
+    }
+
+    fn selected_article(&self) -> Option<(&TreeEntry, Option<&ArticleDataResponse>)> {
+        let entry = &self.entries[self.list_state.selected()?];
+        let article_id = entry.article_id?;
+
+        Some((entry, self.articles.get(&article_id).copied()))
+    }
+
+    // Title and body of the content pane
+    fn selected_text(&self) -> (String, String) {
+        match self.selected_article() {
+            Some((_, Some(article))) => (
+                or_placeholder(
+                    localized(&article.titles, self.args.title_lang()),
+                    "(untitled)",
+                ),
+                or_placeholder(
+                    &self
+                        .args
+                        .contents_format
+                        .to_text(localized(&article.contents, &self.args.lang)),
+                    "(article has no contents)",
+                ),
+            ),
+            Some((entry, None)) => (
+                entry.title.clone(),
+                "(article is not in the local archive)".to_string(),
+            ),
+            None => (String::new(), String::new()),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main_area, status_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [tree_area, content_area] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(main_area);
+
+        let items = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let marker = if entry.article_id.is_some() {
+                    " "
+                } else {
+                    "▸"
+                };
+                ListItem::new(format!(
+                    "{}{} {}",
+                    "  ".repeat(entry.depth),
+                    marker,
+                    entry.title
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        let tree = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Categories"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(tree, tree_area, &mut self.list_state);
+
+        let (title, body) = self.selected_text();
+
+        if self.scrolled_entry != self.list_state.selected() {
+            self.scroll = 0;
+            self.scrolled_entry = self.list_state.selected();
+        }
+
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let inner_area = block.inner(content_area);
+
+        let lines = wrap_text(&body, inner_area.width.saturating_sub(1) as usize);
+        let max_scroll = lines.len().saturating_sub(inner_area.height as usize);
+
+        self.page_height = inner_area.height as usize;
+        self.scroll = self.scroll.min(max_scroll);
+
+        let visible = lines
+            .iter()
+            .skip(self.scroll)
+            .take(inner_area.height as usize)
+            .map(|line| Line::from(line.as_str()))
+            .collect::<Vec<_>>();
+
+        let position = if lines.is_empty() {
+            String::new()
+        } else {
+            format!(
+                " {}-{}/{} ",
+                self.scroll + 1,
+                (self.scroll + visible.len()).min(lines.len()),
+                lines.len()
+            )
+        };
+
+        frame.render_widget(
+            Paragraph::new(visible).block(block.title_bottom(Line::from(position).right_aligned())),
+            content_area,
+        );
+
+        let mut scrollbar_state = ScrollbarState::new(max_scroll).position(self.scroll);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            inner_area,
+            &mut scrollbar_state,
+        );
+
+        let status = match &self.search_input {
+            Some(input) => format!("/{}", input),
+            None => "↑/↓ move  PgUp/PgDn scroll  / search  n next match  q quit".to_string(),
+        };
+
+        frame.render_widget(Line::from(status), status_area);
+    }
+}
+
+// Wraps on display width rather than char count so double-width CJK glyphs never overflow
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = vec![];
+
+    for source_line in text.lines() {
+        let mut line = String::new();
+        let mut line_width = 0;
+
+        for word in source_line.split_inclusive(' ') {
+            let word_width = word.chars().filter_map(|c| c.width()).sum::<usize>();
+
+            if line_width + word_width > width && !line.is_empty() {
+                lines.push(line.trim_end().to_string());
+                line.clear();
+                line_width = 0;
+            }
+
+            for c in word.chars() {
+                let char_width = c.width().unwrap_or(0);
+
+                if line_width + char_width > width && !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+
+                line.push(c);
+                line_width += char_width;
+            }
+        }
+
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn node(
+        id: i32,
+        type_: &str,
+        titles: serde_json::Value,
+        children: &[serde_json::Value],
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "parent_id": null,
+            "position": 0,
+            "type": type_,
+            "status": "PUBLISHED",
+            "titles": titles,
+            "children": children,
+            "modified": false,
+        })
+    }
+
+    fn article(
+        id: i32,
+        titles: serde_json::Value,
+        contents: serde_json::Value,
+    ) -> ArticleDataResponse {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "category_id": 1,
+            "category_titles": {"KR": "분류"},
+            "status": "PUBLISHED",
+            "titles": titles,
+            "subtitles": {},
+            "image_url": null,
+            "attachments": {},
+            "contents": contents,
+        }))
+        .unwrap()
+    }
+
+    fn data() -> CrawlData {
+        let categories = serde_json::json!([node(
+            1,
+            "CATEGORY",
+            serde_json::json!({"KR": "분류", "EN": "Category"}),
+            &[
+                node(
+                    10,
+                    "ARTICLE",
+                    serde_json::json!({"EN": "English only"}),
+                    &[]
+                ),
+                node(11, "ARTICLE", serde_json::json!({"JA": "日本語"}), &[]),
+                node(12, "ARTICLE", serde_json::json!({"KR": "없는 글"}), &[]),
+            ],
+        )]);
+
+        CrawlData {
+            categories: serde_json::from_value(categories).unwrap(),
+            category_names: vec![],
+            ko_articles: vec![
+                article(
+                    10,
+                    serde_json::json!({"EN": "English only"}),
+                    serde_json::json!({"EN": "<p>Body</p>"}),
+                ),
+                article(
+                    11,
+                    serde_json::json!({"JA": "日本語"}),
+                    serde_json::json!({}),
+                ),
+            ],
+            errors: vec![],
+        }
+    }
+
+    #[test]
+    fn flatten_tree_indents_children_and_names_every_entry() {
+        let args = Arguments::parse_from(["dfu_crawler", "--lang", "en"]);
+        let mut entries = vec![];
+        flatten_tree(&args, &data().categories, 0, &mut entries);
+
+        let flattened = entries
+            .iter()
+            .map(|entry| (entry.depth, entry.title.as_str(), entry.article_id))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flattened,
+            [
+                (0, "Category", None),
+                (1, "English only", Some(10)),
+                (1, "(untitled)", Some(11)),
+                (1, "없는 글", Some(12)),
+            ]
+        );
+    }
+
+    #[test]
+    fn articles_without_korean_show_the_chosen_language_or_a_placeholder() {
+        let data = data();
+        let select = |lang: &str, index: usize| {
+            let args = Arguments::parse_from(["dfu_crawler", "--lang", lang]);
+            let mut browser = Browser::new(&args, &data);
+            browser.list_state.select(Some(index));
+            browser.selected_text()
+        };
+
+        assert_eq!(
+            select("en", 1),
+            ("English only".to_string(), "Body".to_string())
+        );
+        assert_eq!(
+            select("kr", 1),
+            (
+                "(untitled)".to_string(),
+                "(article has no contents)".to_string()
+            )
+        );
+        assert_eq!(
+            select("kr", 2),
+            (
+                "(untitled)".to_string(),
+                "(article has no contents)".to_string()
+            )
+        );
+        assert_eq!(
+            select("kr", 3),
+            (
+                "없는 글".to_string(),
+                "(article is not in the local archive)".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::{format_count, report, Arguments, CrawlData, LangEnum};
+
+// The known languages, then the ones the API added later that some article has
+fn langs(data: &CrawlData) -> Vec<LangEnum> {
+    let mut langs = data
+        .ko_articles
+        .iter()
+        .flat_map(|article| article.contents.keys())
+        .filter(|lang| matches!(lang, LangEnum::Other(_)))
+        .cloned()
+        .collect::<Vec<_>>();
+    langs.sort_by_key(LangEnum::order);
+    langs.dedup();
+
+    LangEnum::KNOWN.into_iter().chain(langs).collect()
+}
+
+#[derive(Serialize)]
+pub struct LangCoverage {
+    pub lang: LangEnum,
+    pub articles: usize,
+    pub total_chars: usize,
+    pub avg_chars: usize,
+    // Only counted with --identical-as-untranslated, and then left out of `articles`
+    pub identical: Option<usize>,
+}
+
+// Contents that are byte-identical to those of a language earlier in langs(), usually a
+// placeholder copied over in place of a translation
+pub struct IdenticalContents {
+    pub article_id: i32,
+    pub lang: LangEnum,
+    pub same_as: LangEnum,
+}
+
+pub fn identical_contents(data: &CrawlData) -> Vec<IdenticalContents> {
+    let langs = langs(data);
+    let mut identical = vec![];
+
+    for article in &data.ko_articles {
+        for (index, lang) in langs.iter().enumerate() {
+            let Some(contents) = article.contents.get(lang) else {
+                continue;
+            };
+            if contents.trim().is_empty() {
+                continue;
+            }
+
+            let same_as = langs[..index]
+                .iter()
+                .find(|earlier| article.contents.get(earlier) == Some(contents));
+            if let Some(same_as) = same_as {
+                identical.push(IdenticalContents {
+                    article_id: article.id,
+                    lang: lang.clone(),
+                    same_as: same_as.clone(),
+                });
+            }
+        }
+    }
+
+    identical
+}
+
+// Blank contents count as untranslated; lengths are in characters of the text, markup excluded
+pub fn collect(
+    args: &Arguments,
+    data: &CrawlData,
+    identical: Option<&[IdenticalContents]>,
+) -> Vec<LangCoverage> {
+    let identical_set = identical
+        .unwrap_or_default()
+        .iter()
+        .map(|entry| (entry.article_id, &entry.lang))
+        .collect::<HashSet<_>>();
+
+    langs(data)
+        .into_iter()
+        .map(|lang| {
+            let lengths = data
+                .ko_articles
+                .iter()
+                .filter(|article| !identical_set.contains(&(article.id, &lang)))
+                .filter_map(|article| article.contents.get(&lang))
+                .map(|contents| {
+                    args.contents_format
+                        .to_text(contents)
+                        .trim()
+                        .chars()
+                        .count()
+                })
+                .filter(|length| *length > 0)
+                .collect::<Vec<_>>();
+
+            let total_chars = lengths.iter().sum::<usize>();
+            let identical = identical
+                .map(|identical| identical.iter().filter(|entry| entry.lang == lang).count());
+
+            LangCoverage {
+                lang,
+                articles: lengths.len(),
+                total_chars,
+                avg_chars: total_chars.checked_div(lengths.len()).unwrap_or(0),
+                identical,
+            }
+        })
+        .collect()
+}
+
+pub fn print(
+    args: &Arguments,
+    data: &CrawlData,
+    rows: &[LangCoverage],
+    identical: Option<&[IdenticalContents]>,
+) -> anyhow::Result<()> {
+    report::print(args, rows, || print_table(args, data, rows, identical))
+}
+
+fn print_table(
+    args: &Arguments,
+    data: &CrawlData,
+    rows: &[LangCoverage],
+    identical: Option<&[IdenticalContents]>,
+) {
+    let total = data.ko_articles.len();
+    let cells = rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![
+                row.lang.to_string(),
+                format!(
+                    "{} ({:.1}%)",
+                    format_count(args.locale, row.articles),
+                    row.articles as f64 * 100.0 / total.max(1) as f64
+                ),
+                format_count(args.locale, row.total_chars),
+                format_count(args.locale, row.avg_chars),
+            ];
+            if let Some(count) = row.identical {
+                cells.push(format_count(args.locale, count));
+            }
+            cells
+        })
+        .collect::<Vec<_>>();
+
+    let mut header = vec!["Language", "Articles", "Total chars", "Avg chars"];
+    if identical.is_some() {
+        header.push("Identical");
+    }
+    let widths = (0..header.len())
+        .map(|column| {
+            cells
+                .iter()
+                .map(|row| row[column].len())
+                .chain(std::iter::once(header[column].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    let print_row = |row: &[&str]| {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| match column {
+                0 => format!("{:<width$}", cell),
+                _ => format!("{:>width$}", cell),
+            })
+            .collect::<Vec<_>>();
+        println!("{}", line.join("  ").trim_end());
+    };
+
+    print_row(&header);
+    for row in &cells {
+        print_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+    println!("{} articles in total", format_count(args.locale, total));
+
+    for entry in identical.unwrap_or_default() {
+        println!(
+            "Article {}: {} contents are identical to {}",
+            entry.article_id, entry.lang, entry.same_as
+        );
+    }
+}
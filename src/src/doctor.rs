@@ -0,0 +1,141 @@
+use std::{collections::HashSet, path::Path, time::Duration};
+
+use serde::Serialize;
+
+use crate::{
+    article_url, collect_article_ids, probe, report::ReportFormat, Arguments, CategoryResponse,
+};
+
+#[derive(Serialize)]
+struct Check {
+    passed: bool,
+    message: String,
+}
+
+// Every check runs even when an earlier one fails, so a single run lists every problem
+pub async fn run(args: &Arguments) -> anyhow::Result<()> {
+    let mut checks = vec![];
+    // The table prints each check as it finishes, the slow reachability ones included
+    let mut report = |passed: bool, message: String| {
+        if args.report_format == ReportFormat::Table {
+            println!("[{}] {}", if passed { " ok " } else { "FAIL" }, message);
+        }
+        checks.push(Check { passed, message });
+    };
+
+    let (passed, message) = check_writable(&args.output_dir);
+    report(passed, message);
+
+    let categories_path = args.output_dir.join("category").join("categories.json");
+    let categories = std::fs::read_to_string(&categories_path)
+        .map_err(anyhow::Error::from)
+        .and_then(|body| probe::parse::<CategoryResponse>(&body));
+
+    let mut expected_ids = HashSet::new();
+    match &categories {
+        Ok(categories) => {
+            collect_article_ids(&categories.data, &mut expected_ids);
+            report(
+                true,
+                format!(
+                    "{} lists {} articles",
+                    categories_path.display(),
+                    expected_ids.len()
+                ),
+            );
+        }
+        Err(e) => report(false, format!("{}: {}", categories_path.display(), e)),
+    }
+
+    let articles_path = args.output_dir.join("articles");
+    let cached_ids = cached_article_ids(&articles_path);
+    if categories.is_ok() {
+        let missing = expected_ids.difference(&cached_ids).count();
+        report(
+            missing == 0,
+            format!(
+                "{} of {} listed articles cached in {}",
+                expected_ids.len() - missing,
+                expected_ids.len(),
+                articles_path.display()
+            ),
+        );
+    } else {
+        report(
+            !cached_ids.is_empty(),
+            format!(
+                "{} articles cached in {}",
+                cached_ids.len(),
+                articles_path.display()
+            ),
+        );
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+
+    let (passed, message) = check_reachable(&client, &args.categories_url).await;
+    report(passed, message);
+
+    // Any listed article will do, the point is whether the article host answers at all
+    let sample_id = expected_ids.iter().min().copied().unwrap_or(1);
+    let (passed, message) =
+        check_reachable(&client, &article_url(&args.article_base_url, sample_id)).await;
+    report(passed, message);
+
+    crate::report::print(args, &checks, || {})?;
+
+    let failures = checks.iter().filter(|check| !check.passed).count();
+    anyhow::ensure!(failures == 0, "{} checks failed", failures);
+
+    Ok(())
+}
+
+fn check_writable(output_dir: &Path) -> (bool, String) {
+    if !output_dir.is_dir() {
+        return (
+            false,
+            format!(
+                "{} does not exist; a crawl creates it",
+                output_dir.display()
+            ),
+        );
+    }
+
+    let probe_path = output_dir.join(".doctor");
+    match std::fs::write(&probe_path, b"").and_then(|_| std::fs::remove_file(&probe_path)) {
+        Ok(()) => (true, format!("{} is writable", output_dir.display())),
+        Err(e) => (
+            false,
+            format!("{} is not writable: {}", output_dir.display(), e),
+        ),
+    }
+}
+
+pub fn cached_article_ids(articles_path: &Path) -> HashSet<i32> {
+    let Ok(entries) = std::fs::read_dir(articles_path) else {
+        return HashSet::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let stem = path.file_stem()?.to_str()?.to_string();
+
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+                .then(|| stem.parse().ok())
+                .flatten()
+        })
+        .collect()
+}
+
+async fn check_reachable(client: &reqwest::Client, url: &str) -> (bool, String) {
+    match client.get(url).send().await {
+        Ok(res) if res.status().is_success() => (true, format!("{} is reachable", url)),
+        Ok(res) => (false, format!("{} answered {}", url, res.status())),
+        Err(e) => (false, format!("{} is unreachable: {}", url, e)),
+    }
+}
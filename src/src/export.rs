@@ -0,0 +1,878 @@
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use clap::ValueEnum;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    localized,
+    template::{self, Templates},
+    ArticleAttachment, ArticleDataResponse, LangEnum,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Md,
+    Json,
+    Html,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Md => "md",
+            OutputFormat::Json => "json",
+            OutputFormat::Html => "html",
+            OutputFormat::Csv => "csv",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        OutputFormat::value_variants()
+            .iter()
+            .copied()
+            .find(|format| format.extension() == extension)
+            .ok_or_else(|| {
+                let supported = OutputFormat::value_variants()
+                    .iter()
+                    .map(|format| format!(".{}", format.extension()))
+                    .collect::<Vec<_>>();
+
+                anyhow::anyhow!(
+                    "Cannot infer the output format of {}; supported extensions are {}",
+                    path.display(),
+                    supported.join(", ")
+                )
+            })
+    }
+}
+
+// Keys of a serialized ArticleDataResponse, in declaration order
+pub const ARTICLE_FIELDS: [&str; 9] = [
+    "id",
+    "category_id",
+    "category_titles",
+    "status",
+    "titles",
+    "subtitles",
+    "image_url",
+    "attachments",
+    "contents",
+];
+
+// Accepts the serialized key or its singular form (`title` for `titles`)
+pub fn parse_field(name: &str) -> anyhow::Result<&'static str> {
+    let name = name.trim();
+
+    ARTICLE_FIELDS
+        .iter()
+        .copied()
+        .find(|&field| field == name || field.strip_suffix('s') == Some(name))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown field {}; available fields are {}",
+                name,
+                ARTICLE_FIELDS.join(", ")
+            )
+        })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FlattenNewlines {
+    // Every line break becomes a single space
+    Space,
+    // Line breaks become a literal `\n`
+    Escape,
+}
+
+impl FlattenNewlines {
+    pub fn apply(self, text: &str) -> String {
+        let replacement = match self {
+            FlattenNewlines::Space => " ",
+            FlattenNewlines::Escape => "\\n",
+        };
+
+        text.replace("\r\n", "\n")
+            .replace('\r', "\n")
+            .replace('\n', replacement)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ContentsFormat {
+    // HTML when the contents contain a tag, plain text otherwise
+    Auto,
+    Html,
+    Md,
+}
+
+impl ContentsFormat {
+    pub fn is_html(self, contents: &str) -> bool {
+        match self {
+            ContentsFormat::Auto => contains_tag(contents),
+            ContentsFormat::Html => true,
+            ContentsFormat::Md => false,
+        }
+    }
+
+    // For the text exports: HTML contents lose their markup, anything else stays as it is
+    pub fn to_text(self, contents: &str) -> Cow<'_, str> {
+        match self.is_html(contents) {
+            true => html_to_text(contents).into(),
+            false => contents.into(),
+        }
+    }
+
+    // For the HTML exports: markup found by auto is embedded only once scripts, event handlers
+    // and the like are stripped, an explicit html is trusted as it is, text is escaped
+    pub fn to_html(self, contents: &str) -> Cow<'_, str> {
+        match self {
+            ContentsFormat::Html => contents.into(),
+            _ if self.is_html(contents) => ammonia::clean(contents).into(),
+            _ => escape_html(contents).into(),
+        }
+    }
+}
+
+// `<` followed by a letter or `/` and closed later on, so `a < b` in prose is not taken for markup
+fn contains_tag(text: &str) -> bool {
+    text.match_indices('<').any(|(start, _)| {
+        let rest = &text[start + 1..];
+
+        rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/') && rest.contains('>')
+    })
+}
+
+// Block-level tags become line breaks, everything else only loses its tags
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..start]));
+
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim().to_ascii_lowercase();
+        rest = &rest[start + end + 1..];
+
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+
+        match name {
+            // Script and style bodies are not text, and may hold a `<` of their own
+            "script" | "style" if !tag.starts_with('/') => {
+                let closing = format!("</{}", name);
+                rest = match rest.to_ascii_lowercase().find(&closing) {
+                    Some(close) => rest[close..].split_once('>').map_or("", |(_, after)| after),
+                    None => "",
+                };
+            }
+            "br" | "p" | "div" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                text.push('\n');
+            }
+            _ => {}
+        }
+    }
+    text.push_str(&decode_entities(rest));
+
+    // Runs of block tags leave at most one blank line
+    let mut collapsed = String::with_capacity(text.len());
+    for line in text.lines().map(|line| line.trim_end()) {
+        if line.is_empty() && (collapsed.is_empty() || collapsed.ends_with("\n\n")) {
+            continue;
+        }
+
+        collapsed.push_str(line);
+        collapsed.push('\n');
+    }
+
+    collapsed.trim_end().to_string()
+}
+
+fn decode_entities(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return text.into();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => {
+                let code = entity.strip_prefix('#')?;
+                let code = match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => code.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded.into()
+}
+
+pub enum ExportItem<'a> {
+    Heading { depth: usize, title: &'a str },
+    Article(&'a ArticleDataResponse),
+}
+
+pub struct ArticleWriter {
+    pub format: OutputFormat,
+    lang: LangEnum,
+    title_lang: LangEnum,
+    fields: Vec<&'static str>,
+    content_delimiter: Option<String>,
+    prefix_ids: bool,
+    inline_langs: Vec<LangEnum>,
+    code_fences: bool,
+    templates: Option<Arc<Templates>>,
+    merge_attachments: bool,
+    contents_format: ContentsFormat,
+    url_template: Option<String>,
+    combine_subtitles: bool,
+    flatten_newlines: Option<FlattenNewlines>,
+    content_wrapper: (String, String),
+    lang_columns: Vec<LangEnum>,
+    entries: Vec<String>,
+}
+
+impl ArticleWriter {
+    pub fn new(format: OutputFormat, lang: LangEnum) -> Self {
+        Self {
+            format,
+            title_lang: lang.clone(),
+            lang,
+            fields: vec![],
+            content_delimiter: None,
+            prefix_ids: false,
+            inline_langs: vec![],
+            code_fences: true,
+            templates: None,
+            merge_attachments: false,
+            contents_format: ContentsFormat::Auto,
+            url_template: None,
+            combine_subtitles: false,
+            flatten_newlines: None,
+            content_wrapper: (String::new(), String::new()),
+            lang_columns: vec![],
+            entries: vec![],
+        }
+    }
+
+    // Titles and category names in another language than the contents
+    pub fn with_title_lang(mut self, title_lang: LangEnum) -> Self {
+        self.title_lang = title_lang;
+        self
+    }
+
+    // Projects JSON entries down to these keys; empty keeps every field
+    pub fn with_fields(mut self, fields: Vec<&'static str>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    // Reduces markdown entries to the bare contents, joined by the delimiter
+    pub fn with_content_only(mut self, delimiter: Option<String>) -> Self {
+        self.content_delimiter = delimiter;
+        self
+    }
+
+    // Markdown headings start with `[id] ` to find the source JSON of any section
+    pub fn with_prefixed_ids(mut self, prefix_ids: bool) -> Self {
+        self.prefix_ids = prefix_ids;
+        self
+    }
+
+    // Markdown articles stack the contents of these languages, in this order, under subheadings
+    pub fn with_inline_langs(mut self, langs: Vec<LangEnum>) -> Self {
+        self.inline_langs = langs;
+        self
+    }
+
+    // Markdown titles as ```[title]``` (rendered as code) or, without fences, in bold
+    pub fn with_code_fences(mut self, code_fences: bool) -> Self {
+        self.code_fences = code_fences;
+        self
+    }
+
+    // Markdown and HTML entries come from the entry.md / entry.html template when there is one
+    pub fn with_templates(mut self, templates: Option<Arc<Templates>>) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    // Appends the attachments to the markdown and HTML entries, so media only listed there is kept
+    pub fn with_merged_attachments(mut self, merge_attachments: bool) -> Self {
+        self.merge_attachments = merge_attachments;
+        self
+    }
+
+    pub fn with_contents_format(mut self, contents_format: ContentsFormat) -> Self {
+        self.contents_format = contents_format;
+        self
+    }
+
+    // HTML titles link to the public page of the article
+    pub fn with_url_template(mut self, url_template: Option<String>) -> Self {
+        self.url_template = url_template;
+        self
+    }
+
+    // Markdown headings read `title — subtitle` when the article has a subtitle
+    pub fn with_combined_subtitles(mut self, combine_subtitles: bool) -> Self {
+        self.combine_subtitles = combine_subtitles;
+        self
+    }
+
+    // JSON and CSV contents on one line, so every record stays on a single line of its own
+    pub fn with_flattened_newlines(mut self, flatten_newlines: Option<FlattenNewlines>) -> Self {
+        self.flatten_newlines = flatten_newlines;
+        self
+    }
+
+    // Markdown, JSON and CSV contents between this prefix and suffix, where {title} and
+    // {category} stand for the article's own
+    pub fn with_content_wrapper(mut self, prefix: String, suffix: String) -> Self {
+        self.content_wrapper = (prefix, suffix);
+        self
+    }
+
+    fn wrap<'a>(&self, article: &ArticleDataResponse, contents: Cow<'a, str>) -> Cow<'a, str> {
+        let (prefix, suffix) = &self.content_wrapper;
+        if prefix.is_empty() && suffix.is_empty() {
+            return contents;
+        }
+
+        let fill = |text: &str| {
+            text.replace("{title}", localized(&article.titles, &self.title_lang))
+                .replace(
+                    "{category}",
+                    localized(&article.category_titles, &self.title_lang),
+                )
+        };
+
+        format!("{}{}{}", fill(prefix), contents, fill(suffix)).into()
+    }
+
+    // CSV rows carry a title and contents column per language, in this order, instead of the
+    // single title and contents of --lang; empty keeps the single-language layout
+    pub fn with_lang_columns(mut self, langs: Vec<LangEnum>) -> Self {
+        self.lang_columns = langs;
+        self
+    }
+
+    fn article_url(&self, id: i32) -> Option<String> {
+        self.url_template
+            .as_ref()
+            .map(|template| fill_url_template(template, id))
+    }
+
+    // A single format keeps the user-chosen name, several formats swap in their own extension
+    pub fn output_path(&self, articles_file: &Path, single_format: bool) -> PathBuf {
+        if single_format {
+            articles_file.to_path_buf()
+        } else {
+            articles_file.with_extension(self.format.extension())
+        }
+    }
+
+    pub fn push_item(&mut self, item: &ExportItem) -> anyhow::Result<()> {
+        match *item {
+            ExportItem::Heading { depth, title } => self.push_heading(depth, title),
+            ExportItem::Article(article) => self.push(article)?,
+        }
+
+        Ok(())
+    }
+
+    // Only the document formats carry category headings, JSON and CSV stay one record per article
+    pub fn push_heading(&mut self, depth: usize, title: &str) {
+        match self.format {
+            OutputFormat::Md if self.content_delimiter.is_none() => {
+                self.entries
+                    .push(format!("{} {}\n", "#".repeat((depth + 1).min(6)), title));
+            }
+            OutputFormat::Html => {
+                let level = (depth + 1).min(6);
+                self.entries
+                    .push(format!("<h{}>{}</h{}>", level, escape_html(title), level));
+            }
+            _ => {}
+        }
+    }
+
+    pub fn push(&mut self, article: &ArticleDataResponse) -> anyhow::Result<()> {
+        let title = localized(&article.titles, &self.title_lang);
+        let raw_contents = localized(&article.contents, &self.lang);
+        let contents = self.wrap(article, self.contents_format.to_text(raw_contents));
+        let contents = contents.as_ref();
+
+        let template = match self.format {
+            OutputFormat::Md if self.content_delimiter.is_none() => Some(template::MD_ENTRY),
+            OutputFormat::Html => Some(template::HTML_ENTRY),
+            _ => None,
+        };
+        if let (Some(templates), Some(name)) = (&self.templates, template) {
+            if templates.has(name) {
+                let url = self.article_url(article.id);
+                let entry = templates.render(
+                    name,
+                    article,
+                    self.lang.clone(),
+                    self.title_lang.clone(),
+                    url.as_deref(),
+                )?;
+                self.entries.push(entry);
+
+                return Ok(());
+            }
+        }
+
+        let entry = match self.format {
+            OutputFormat::Md if self.content_delimiter.is_some() => contents.to_string(),
+            OutputFormat::Md => {
+                let subtitle = localized(&article.subtitles, &self.title_lang).trim();
+                let title = match self.combine_subtitles && !subtitle.is_empty() {
+                    true => format!("{} — {}", title, subtitle),
+                    false => title.to_string(),
+                };
+
+                let heading = match self.prefix_ids {
+                    true => format!("[{}] {}", article.id, title),
+                    false => title,
+                };
+
+                // The trailing backslash is a hard line break
+                let heading = match self.code_fences {
+                    true => format!("```[{}]```\\", heading),
+                    false => format!("**{}**\\", heading),
+                };
+
+                let attachments = match self.merge_attachments {
+                    true => markdown_attachments(article, self.lang.clone()),
+                    false => String::new(),
+                };
+
+                if self.inline_langs.is_empty() {
+                    format!("{}\n{}{}\n\n\n\n", heading, contents, attachments)
+                } else {
+                    let sections = self
+                        .inline_langs
+                        .iter()
+                        .filter_map(|lang| {
+                            let contents = article.contents.get(lang)?;
+                            Some(format!(
+                                "#### {}\n{}\n",
+                                lang,
+                                self.wrap(article, self.contents_format.to_text(contents))
+                            ))
+                        })
+                        .collect::<Vec<_>>();
+
+                    format!("{}\n{}{}\n\n\n", heading, sections.join("\n"), attachments)
+                }
+            }
+            OutputFormat::Json
+                if self.fields.is_empty()
+                    && self.flatten_newlines.is_none()
+                    && self.content_wrapper == Default::default() =>
+            {
+                serde_json::to_string_pretty(article)?
+            }
+            OutputFormat::Json => {
+                let serde_json::Value::Object(mut object) = serde_json::to_value(article)? else {
+                    unreachable!("articles serialize to JSON objects");
+                };
+                if !self.fields.is_empty() {
+                    object.retain(|key, _| self.fields.contains(&key.as_str()));
+                }
+
+                // Wrapped first, so a multi-line prefix is flattened along with the contents
+                let contents = object.get_mut("contents").and_then(|c| c.as_object_mut());
+                for text in contents.into_iter().flat_map(|contents| contents.values_mut()) {
+                    if let Some(contents) = text.as_str() {
+                        let wrapped = self.wrap(article, Cow::Borrowed(contents));
+                        *text = self.flatten(&wrapped).into_owned().into();
+                    }
+                }
+
+                // Flattened contents are asked for to get one record per line, so no pretty printing
+                match self.flatten_newlines {
+                    Some(_) => serde_json::to_string(&object)?,
+                    None => serde_json::to_string_pretty(&object)?,
+                }
+            }
+            OutputFormat::Html => format!(
+                "<article id=\"article-{}\">\n<h2>{}</h2>\n<div class=\"content\">{}</div>\n{}</article>",
+                article.id,
+                match self.article_url(article.id) {
+                    Some(url) => format!("<a href=\"{}\">{}</a>", escape_html(&url), escape_html(title)),
+                    None => escape_html(title),
+                },
+                self.contents_format.to_html(raw_contents),
+                match self.merge_attachments {
+                    true => html_attachments(article, self.lang.clone()),
+                    false => String::new(),
+                }
+            ),
+            OutputFormat::Csv => {
+                let mut row = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(vec![]);
+                let category = localized(&article.category_titles, &self.title_lang);
+
+                if self.lang_columns.is_empty() {
+                    row.serialize((article.id, category, title, self.flatten(contents)))?;
+                } else {
+                    let mut record = vec![article.id.to_string(), category.to_string()];
+                    // No fallback to Korean here, a missing translation stays an empty cell
+                    for lang in &self.lang_columns {
+                        let title = article.titles.get(lang).map_or("", String::as_str);
+                        let contents = article
+                            .contents
+                            .get(lang)
+                            .map(|contents| {
+                                self.wrap(article, self.contents_format.to_text(contents))
+                            })
+                            .unwrap_or_default();
+
+                        record.push(title.to_string());
+                        record.push(self.flatten(&contents).into_owned());
+                    }
+                    row.write_record(&record)?;
+                }
+
+                String::from_utf8(row.into_inner()?)?
+            }
+        };
+
+        self.entries.push(entry);
+
+        Ok(())
+    }
+
+    fn flatten<'a>(&self, contents: &'a str) -> Cow<'a, str> {
+        match self.flatten_newlines {
+            Some(flatten) => flatten.apply(contents).into(),
+            None => Cow::Borrowed(contents),
+        }
+    }
+
+    pub fn finish(&self) -> String {
+        match self.format {
+            OutputFormat::Md => match &self.content_delimiter {
+                Some(delimiter) => self.entries.join(delimiter),
+                None => self.entries.join("\n"),
+            },
+            OutputFormat::Json => format!("[\n{}\n]", self.entries.join(",\n")),
+            OutputFormat::Html => format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<style>.content {{ white-space: pre-wrap; }}</style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+                self.entries.join("\n")
+            ),
+            OutputFormat::Csv if self.lang_columns.is_empty() => {
+                format!("id,category,title,contents\n{}", self.entries.concat())
+            }
+            OutputFormat::Csv => {
+                let columns = self
+                    .lang_columns
+                    .iter()
+                    .map(|lang| {
+                        let code = lang.code().to_lowercase();
+                        format!("title_{},content_{}", code, code)
+                    })
+                    .collect::<Vec<_>>();
+
+                format!(
+                    "id,category,{}\n{}",
+                    columns.join(","),
+                    self.entries.concat()
+                )
+            }
+        }
+    }
+}
+
+// Languages with contents in any exported article, in enum order
+pub fn present_langs(items: &[ExportItem]) -> Vec<LangEnum> {
+    LangEnum::KNOWN
+        .into_iter()
+        .filter(|lang| {
+            items.iter().any(|item| match item {
+                ExportItem::Article(article) => article.contents.contains_key(lang),
+                ExportItem::Heading { .. } => false,
+            })
+        })
+        .collect()
+}
+
+// Falls back to the Korean list like localized(), ordered by position
+pub fn sorted_attachments(
+    article: &ArticleDataResponse,
+    lang: LangEnum,
+) -> Vec<&ArticleAttachment> {
+    let mut attachments = article
+        .attachments
+        .get(&lang)
+        .or_else(|| article.attachments.get(&LangEnum::KR))
+        .map(|attachments| attachments.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    attachments.retain(|attachment| !attachment.source_url.is_empty());
+    attachments.sort_by_key(|attachment| (attachment.position, attachment.id));
+
+    attachments
+}
+
+fn markdown_attachments(article: &ArticleDataResponse, lang: LangEnum) -> String {
+    sorted_attachments(article, lang)
+        .iter()
+        .map(|attachment| {
+            let label = format!("{} {}", attachment.type_, attachment.position);
+
+            match attachment.type_.as_str() {
+                "IMAGE" => format!("\n\n![{}]({})", label, attachment.source_url),
+                _ => format!("\n\n[{}]({})", label, attachment.source_url),
+            }
+        })
+        .collect()
+}
+
+pub fn html_attachments(article: &ArticleDataResponse, lang: LangEnum) -> String {
+    let attachments = sorted_attachments(article, lang);
+    if attachments.is_empty() {
+        return String::new();
+    }
+
+    let items = attachments
+        .iter()
+        .map(|attachment| {
+            let label = escape_html(&format!("{} {}", attachment.type_, attachment.position));
+            let url = escape_html(&attachment.source_url);
+
+            match attachment.type_.as_str() {
+                "IMAGE" => format!("<img src=\"{}\" alt=\"{}\">\n", url, label),
+                _ => format!("<a href=\"{}\">{}</a>\n", url, label),
+            }
+        })
+        .collect::<String>();
+
+    format!("<div class=\"attachments\">\n{}</div>\n", items)
+}
+
+pub fn fill_url_template(template: &str, id: i32) -> String {
+    template.replace("{id}", &id.to_string())
+}
+
+// Standalone page for a single article, with OpenGraph tags for link previews
+pub fn article_page(
+    article: &ArticleDataResponse,
+    lang: LangEnum,
+    title_lang: LangEnum,
+    snippet_length: usize,
+    contents_format: ContentsFormat,
+    canonical_url: Option<&str>,
+) -> String {
+    let title = localized(&article.titles, &title_lang);
+    let raw_contents = localized(&article.contents, &lang);
+    let contents = contents_format.to_text(raw_contents);
+    let contents = contents.as_ref();
+
+    let subtitle = localized(&article.subtitles, &lang).trim();
+    let description = if subtitle.is_empty() {
+        snippet(contents, snippet_length)
+    } else {
+        snippet(subtitle, snippet_length)
+    };
+
+    let mut meta = vec![];
+    if !title.is_empty() {
+        meta.push(og_tag("og:title", title));
+    }
+    if let Some(image_url) = article.image_url.as_deref().filter(|url| !url.is_empty()) {
+        meta.push(og_tag("og:image", image_url));
+    }
+    if !description.is_empty() {
+        meta.push(og_tag("og:description", &description));
+    }
+    if let Some(url) = canonical_url {
+        meta.push(og_tag("og:url", url));
+        meta.push(format!(
+            "<link rel=\"canonical\" href=\"{}\">\n",
+            escape_html(url)
+        ));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n{}<title>{}</title>\n<style>.content {{ white-space: pre-wrap; }}</style>\n</head>\n<body>\n<h1>{}</h1>\n<div class=\"content\">{}</div>\n</body>\n</html>\n",
+        meta.concat(),
+        escape_html(title),
+        escape_html(title),
+        contents_format.to_html(raw_contents)
+    )
+}
+
+// Whitespace collapsed to single spaces, cut after `length` graphemes so neither a multi-byte
+// character nor a combining sequence is split
+pub fn snippet(text: &str, length: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    match text.grapheme_indices(true).nth(length) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+fn og_tag(property: &str, content: &str) -> String {
+    format!(
+        "<meta property=\"{}\" content=\"{}\">\n",
+        property,
+        escape_html(content)
+    )
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_tag_needs_a_tag_name_and_a_closing_bracket() {
+        assert!(contains_tag("<p>text</p>"));
+        assert!(contains_tag("line</br>"));
+        assert!(!contains_tag("a < b and c > d"));
+        assert!(!contains_tag("1 <2"));
+        assert!(!contains_tag("<p unterminated"));
+        assert!(!contains_tag("plain text"));
+    }
+
+    #[test]
+    fn html_to_text_keeps_block_breaks_and_drops_scripts() {
+        assert_eq!(
+            html_to_text("<p>First</p><p>Second<br>line</p>"),
+            "First\n\nSecond\nline"
+        );
+        assert_eq!(
+            html_to_text("<div>Kept</div><script>if (a < b) alert(1)</script><style>p {}</style>"),
+            "Kept"
+        );
+        assert_eq!(html_to_text("<p>a</p><p></p><p></p><p>b</p>"), "a\n\nb");
+        assert_eq!(html_to_text("<b>Tom &amp; Jerry</b>"), "Tom & Jerry");
+        assert_eq!(html_to_text("cut <b"), "cut <b");
+    }
+
+    #[test]
+    fn decode_entities_knows_named_and_numeric_references() {
+        assert_eq!(
+            decode_entities("&lt;a&gt; &quot;b&quot; &apos;c&apos;"),
+            "<a> \"b\" 'c'"
+        );
+        assert_eq!(decode_entities("&#54620;&#xAE00; &#X41;"), "한글 A");
+        assert_eq!(decode_entities("a&nbsp;b"), "a b");
+        // Unknown, unterminated and invalid references are left as they are
+        assert_eq!(decode_entities("&copy; & &amp"), "&copy; & &amp");
+        assert_eq!(decode_entities("&#xD800; &#bad;"), "&#xD800; &#bad;");
+        assert!(matches!(decode_entities("no entities"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn to_html_only_trusts_markup_when_told_to() {
+        let contents =
+            r#"<p onclick="steal()">Hi</p><script>steal()</script><img src=x onerror=steal()>"#;
+
+        let auto = ContentsFormat::Auto.to_html(contents);
+        assert!(auto.contains("<p>Hi</p>"), "{}", auto);
+        assert!(!auto.contains("script"), "{}", auto);
+        assert!(!auto.contains("steal"), "{}", auto);
+
+        assert_eq!(ContentsFormat::Html.to_html(contents), contents);
+        assert_eq!(ContentsFormat::Md.to_html("<b>&"), "&lt;b&gt;&amp;");
+        assert_eq!(ContentsFormat::Auto.to_html("a < b"), "a &lt; b");
+    }
+
+    #[test]
+    fn inline_languages_are_headed_by_their_code() {
+        let article = serde_json::from_value::<ArticleDataResponse>(serde_json::json!({
+            "id": 1,
+            "category_id": 1,
+            "category_titles": {"KR": "분류"},
+            "status": "PUBLISHED",
+            "titles": {"KR": "제목"},
+            "subtitles": {"KR": ""},
+            "image_url": null,
+            "attachments": {},
+            "contents": {"KR": "본문", "ja": "本文"},
+        }))
+        .unwrap();
+
+        let mut writer =
+            ArticleWriter::new(OutputFormat::Md, LangEnum::KR).with_inline_langs(vec![
+                LangEnum::KR,
+                LangEnum::Other("ja".to_string()),
+                LangEnum::EN,
+            ]);
+        writer.push(&article).unwrap();
+
+        assert_eq!(
+            writer.finish(),
+            "```[제목]```\\\n#### KR\n본문\n\n#### ja\n本文\n\n\n\n"
+        );
+    }
+}
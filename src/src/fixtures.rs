@@ -0,0 +1,163 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde_json::{json, Value};
+
+use crate::{write_atomic, Arguments};
+
+const ARTICLES_PER_CATEGORY: usize = 100;
+
+const WORDS: [&str; 12] = [
+    "던전앤파이터",
+    "유니버스",
+    "모험가",
+    "아라드",
+    "대륙",
+    "전설",
+    "the",
+    "sword",
+    "of",
+    "light",
+    "and",
+    "shadow",
+];
+
+fn titles(text: &str, langs: &[&str]) -> Value {
+    langs
+        .iter()
+        .map(|lang| (lang.to_string(), json!(format!("{} {}", text, lang))))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+fn category(id: i32, parent_id: Option<i32>, position: usize, children: Vec<Value>) -> Value {
+    json!({
+        "id": id,
+        "parent_id": parent_id,
+        "position": position,
+        "type": "CATEGORY",
+        "status": "PUBLISHED",
+        "titles": titles(&format!("Category {}", id), &["KR", "EN", "CN"]),
+        "children": children,
+        "modified": false,
+    })
+}
+
+// Lays out <output-dir> the way a finished crawl leaves it, with articles spread over two
+// levels of categories and the odd missing translation, so --use-local has something to chew on
+pub fn generate(args: &Arguments, count: usize, seed: u64) -> anyhow::Result<()> {
+    let category_dir = args.output_dir.join("category");
+    let articles_dir = args.output_dir.join("articles");
+    std::fs::create_dir_all(&category_dir).unwrap();
+    std::fs::create_dir_all(&articles_dir).unwrap();
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let root_count = count.div_ceil(ARTICLES_PER_CATEGORY).max(1);
+    // Per root: the articles linked directly and those of its one subcategory
+    let mut children = vec![(vec![], vec![]); root_count];
+
+    for index in 0..count {
+        let id = 1000 + index as i32;
+        let root = index % root_count;
+        let nested = rng.gen_bool(0.5);
+        let category_id = match nested {
+            true => 2 * root as i32 + 2,
+            false => 2 * root as i32 + 1,
+        };
+
+        let langs = match rng.gen_range(0..4) {
+            0 => vec!["KR"],
+            1 => vec!["KR", "EN"],
+            _ => vec!["KR", "EN", "CN"],
+        };
+        let title = format!("Article {}", id);
+
+        let contents = langs
+            .iter()
+            .map(|lang| {
+                let paragraphs = (0..rng.gen_range(1..6))
+                    .map(|_| {
+                        (0..rng.gen_range(5..40))
+                            .map(|_| *WORDS.choose(&mut rng).unwrap())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<_>>();
+
+                (lang.to_string(), json!(paragraphs.join("\n\n")))
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        let attachments = match rng.gen_bool(0.3) {
+            true => json!({ "KR": [{
+                "id": id,
+                "type": "IMAGE",
+                "position": 0,
+                "source_url": format!("https://example.com/images/{}.png", id),
+                "thumbnail_url": format!("https://example.com/images/{}_thumb.png", id),
+                "modified": false,
+                "status": "PUBLISHED",
+            }] }),
+            false => json!({}),
+        };
+
+        let article = json!({
+            "code": "0",
+            "message": "OK",
+            "data": {
+                "id": id,
+                "category_id": category_id,
+                "category_titles": titles(&format!("Category {}", category_id), &["KR", "EN", "CN"]),
+                "status": "PUBLISHED",
+                "titles": titles(&title, &langs),
+                "subtitles": titles("Subtitle", &langs),
+                "image_url": null,
+                "attachments": attachments,
+                "contents": contents,
+            },
+        });
+        write_atomic(
+            &articles_dir.join(format!("{}.json", id)),
+            article.to_string().as_bytes(),
+        )?;
+
+        let node = json!({
+            "id": id,
+            "parent_id": category_id,
+            "position": index,
+            "type": "ARTICLE",
+            "status": "PUBLISHED",
+            "titles": titles(&title, &langs),
+            "children": [],
+            "modified": false,
+        });
+        match nested {
+            true => children[root].1.push(node),
+            false => children[root].0.push(node),
+        }
+    }
+
+    let roots = children
+        .into_iter()
+        .enumerate()
+        .map(|(root, (mut direct, nested))| {
+            let id = 2 * root as i32 + 1;
+            direct.push(category(id + 1, Some(id), direct.len(), nested));
+            category(id, None, root, direct)
+        })
+        .collect::<Vec<_>>();
+
+    write_atomic(
+        &category_dir.join("categories.json"),
+        json!({ "code": "0", "message": "OK", "data": roots })
+            .to_string()
+            .as_bytes(),
+    )?;
+
+    println!(
+        "Wrote {} articles in {} categories into {}",
+        count,
+        root_count * 2,
+        args.output_dir.display()
+    );
+
+    Ok(())
+}
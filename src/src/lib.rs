@@ -0,0 +1,5514 @@
+mod browse;
+mod coverage;
+mod doctor;
+mod export;
+mod fixtures;
+mod probe;
+mod prune;
+mod query;
+mod report;
+mod serve;
+mod template;
+mod watch;
+mod wordfreq;
+
+use async_recursion::async_recursion;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs::File,
+    io::{IsTerminal, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use clap::{ColorChoice, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use export::{ArticleWriter, ContentsFormat, ExportItem, FlattenNewlines, OutputFormat};
+use futures::StreamExt;
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::prelude::*;
+use report::ReportFormat;
+use reqwest::{
+    header::{CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    StatusCode,
+};
+use robotstxt::DefaultMatcher;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use template::Templates;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use unicode_normalization::UnicodeNormalization;
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum LangEnum {
+    KR,
+    EN,
+    CN,
+    // Languages the API adds later (e.g. JP) keep their code here instead of failing the parse
+    Other(String),
+}
+
+impl LangEnum {
+    const KNOWN: [LangEnum; 3] = [LangEnum::KR, LangEnum::EN, LangEnum::CN];
+
+    // The known languages in enum order, then the others by code
+    fn order(&self) -> (usize, String) {
+        match self {
+            LangEnum::Other(code) => (LangEnum::KNOWN.len(), code.clone()),
+            known => (
+                LangEnum::KNOWN
+                    .iter()
+                    .position(|lang| lang == known)
+                    .unwrap(),
+                String::new(),
+            ),
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        match self {
+            LangEnum::KR => "KR",
+            LangEnum::EN => "EN",
+            LangEnum::CN => "CN",
+            LangEnum::Other(code) => code,
+        }
+    }
+}
+
+impl std::fmt::Display for LangEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl Serialize for LangEnum {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for LangEnum {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+
+        Ok(match code.as_str() {
+            "KR" => LangEnum::KR,
+            "EN" => LangEnum::EN,
+            "CN" => LangEnum::CN,
+            _ => LangEnum::Other(code),
+        })
+    }
+}
+
+// Only the known languages can be asked for on the command line
+impl ValueEnum for LangEnum {
+    fn value_variants<'a>() -> &'a [Self] {
+        static VARIANTS: [LangEnum; 3] = LangEnum::KNOWN;
+        &VARIANTS
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        match self {
+            LangEnum::Other(_) => None,
+            LangEnum::KR => Some(clap::builder::PossibleValue::new("kr")),
+            LangEnum::EN => Some(clap::builder::PossibleValue::new("en")),
+            LangEnum::CN => Some(clap::builder::PossibleValue::new("cn")),
+        }
+    }
+}
+
+// Any language code is accepted (see LangEnum::Other), so the schema only suggests the known ones
+impl JsonSchema for LangEnum {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "LangEnum".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "examples": ["KR", "EN", "CN"]
+        })
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, JsonSchema)]
+struct CategoryResponse {
+    pub code: String,
+    pub message: String,
+    pub data: Vec<CategoryChildResponse>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+struct CategoryChildResponse {
+    pub id: i32,
+    pub parent_id: Option<i32>,
+    pub position: i32,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub status: String,
+    pub titles: HashMap<LangEnum, String>,
+    pub children: Vec<CategoryChildResponse>,
+    pub modified: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ArticleResponse {
+    pub code: String,
+    pub message: String,
+    pub data: ArticleDataResponse,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ArticleDataResponse {
+    pub id: i32,
+    pub category_id: i32,
+    pub category_titles: HashMap<LangEnum, String>,
+    pub status: String,
+    pub titles: HashMap<LangEnum, String>,
+    pub subtitles: HashMap<LangEnum, String>,
+    pub image_url: Option<String>,
+    pub attachments: HashMap<LangEnum, Vec<ArticleAttachment>>,
+    pub contents: HashMap<LangEnum, String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ArticleAttachment {
+    pub id: i32,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub position: i32,
+    pub source_url: String,
+    pub thumbnail_url: String,
+    pub modified: bool,
+    pub status: String,
+}
+
+#[deprecated(note = "renamed to ArticleAttachment")]
+pub type ArticleAattachment = ArticleAttachment;
+
+#[derive(Parser, Debug, Clone)]
+pub struct Arguments {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(short, long)]
+    use_local: bool,
+
+    /// Store the exact bytes of every response under <output-dir>/raw before parsing
+    #[arg(long)]
+    archive_raw: bool,
+
+    /// How many times a failed request is retried before giving up
+    #[arg(long, default_value_t = 3)]
+    retries: u32,
+
+    /// Restrict the crawl/export to the subtree rooted at this category id
+    #[arg(long)]
+    category_id: Option<i32>,
+
+    /// Collapse single-child category chains into one "Parent / Child" entry in the tree output
+    #[arg(long)]
+    flatten_single_child: bool,
+
+    /// Write category_tree.md as headings whose level follows the category depth (# to ######)
+    /// instead of an indented list
+    #[arg(long)]
+    category_depth_labels: bool,
+
+    /// Write the combined articles to stdout instead of all_articles.md (implies no progress output)
+    #[arg(long)]
+    stdout: bool,
+
+    /// File name (or path) of the combined articles export, relative to <output-dir>/final
+    #[arg(long, default_value = "all_articles.md")]
+    articles_file: PathBuf,
+
+    /// File name (or path) of the category names export, relative to <output-dir>/final
+    #[arg(long, default_value = "category_names.txt")]
+    categories_file: PathBuf,
+
+    /// Global cap on outgoing requests per second, shared by every fetch
+    #[arg(long, default_value_t = 1.0)]
+    rps: f64,
+
+    /// Upper bound on the delay between two retries of the same request
+    #[arg(long, default_value_t = 30)]
+    max_backoff_secs: u64,
+
+    /// Total time a single request may spend retrying before it is abandoned
+    #[arg(long, default_value_t = 120)]
+    retry_deadline_secs: u64,
+
+    /// After exporting, check every image, attachment and article URL and list the dead ones
+    /// in final/broken_links.csv (.json with --report-format json)
+    #[arg(long)]
+    validate_links: bool,
+
+    /// Download every article attachment into <output-dir>/attachments
+    #[arg(long)]
+    download_attachments: bool,
+
+    /// Save attachments as {article_id}_{attachment_id}.ext in one directory instead of a folder per article
+    #[arg(long)]
+    flat_attachments: bool,
+
+    /// Name attachment files after their id, or after the basename of their URL (suffixed with the id on a clash)
+    #[arg(long, value_enum, default_value = "id")]
+    attachment_name_from: AttachmentNameFrom,
+
+    /// Download an attachment URL shared by several articles only once, into attachments/shared,
+    /// and symlink every article's file to that copy
+    #[arg(long)]
+    dedupe_attachments_global: bool,
+
+    /// Keep attachment files that already exist and are not empty instead of downloading them again
+    #[arg(long)]
+    skip_existing_attachments: bool,
+
+    /// With --skip-existing-attachments, keep an existing file only if it matches the sha256
+    /// recorded in the previous attachments/manifest.json
+    #[arg(long, requires = "skip_existing_attachments")]
+    verify_attachment_hashes: bool,
+
+    /// Write a report of articles without an image (or with attachments that 404)
+    #[arg(long)]
+    missing_images_report: bool,
+
+    /// Comma separated list of article export formats, all written in a single pass
+    /// (defaults to the --output-file extension, or md)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    output_format: Vec<OutputFormat>,
+
+    /// Comma separated article fields kept in the JSON export (e.g. id,title,contents)
+    #[arg(long, value_delimiter = ',')]
+    select_fields: Vec<String>,
+
+    /// Order the articles by the category tree and precede each category with a heading (markdown
+    /// and html); the default is one flat list
+    #[arg(long)]
+    hierarchical: bool,
+
+    /// Write only the article contents in the markdown export, without titles or code fences
+    #[arg(long)]
+    content_only: bool,
+
+    /// Separator between articles in --content-only mode (`\n` and `\t` are unescaped)
+    #[arg(long, default_value = "\\n\\n")]
+    content_delimiter: String,
+
+    /// Text put before the contents of every markdown, JSON and CSV article, e.g. for a prompt
+    /// template; {title} and {category} are filled in and `\n` and `\t` are unescaped
+    #[arg(long)]
+    content_prefix: Option<String>,
+
+    /// Text put after the contents of every markdown, JSON and CSV article, like --content-prefix
+    #[arg(long)]
+    content_suffix: Option<String>,
+
+    /// Path of the combined articles export, or `-` for stdout; replaces --articles-file
+    #[arg(long)]
+    output_file: Option<PathBuf>,
+
+    /// Resume an interrupted crawl from a checkpoint file (<output-dir>/checkpoint.json)
+    #[arg(long)]
+    resume_from: Option<PathBuf>,
+
+    /// Reuse the cached copy of every article listed in <output-dir>/completed.json instead of fetching it
+    #[arg(long)]
+    incremental: bool,
+
+    /// Serve any URL fetched before (in this run or an earlier one) from <output-dir>/http_cache
+    #[arg(long)]
+    cache_http: bool,
+
+    /// Seconds after which a --cache-http entry is fetched again (never by default)
+    #[arg(long, requires = "cache_http")]
+    cache_ttl: Option<u64>,
+
+    /// Keep the fetched JSON in memory only, without writing <output-dir>/articles and <output-dir>/category
+    #[arg(long, conflicts_with_all = ["use_local", "resume_from", "incremental"])]
+    no_cache_write: bool,
+
+    /// Re-download article and category JSON whose saved size differs from Content-Length
+    #[arg(long)]
+    verify_downloads: bool,
+
+    /// Only fetch articles whose listing title contains this keyword (case-insensitive, any language)
+    #[arg(long)]
+    title_contains: Option<String>,
+
+    /// Language of the exported titles and contents (category names and excludes stay Korean)
+    #[arg(long, value_enum, default_value = "kr")]
+    lang: LangEnum,
+
+    /// Comma separated languages to export instead of --lang alone, each into its own file named
+    /// after it (e.g. all_articles.en.md)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    export_langs: Vec<LangEnum>,
+
+    /// Language of the article and category titles and headings (defaults to --lang)
+    #[arg(long, value_enum)]
+    category_title_lang: Option<LangEnum>,
+
+    /// What to do when an export file already exists
+    #[arg(long, value_enum, default_value = "overwrite")]
+    overwrite_policy: OverwritePolicy,
+
+    /// Also write every article as its own HTML page (with OpenGraph tags) under <output-dir>/final/articles
+    #[arg(long)]
+    article_pages: bool,
+
+    /// Nest the --article-pages as articles/<category>/<id>-<title>.html, named after the
+    /// --category-title-lang titles with anything a file system might reject replaced
+    #[arg(long, requires = "article_pages")]
+    group_output_by_category: bool,
+
+    /// Print a table of the fetched article count per top-level category when the crawl ends
+    #[arg(long)]
+    pretty_progress: bool,
+
+    /// Print only every Nth fetched article, errors are still reported in full
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    progress_every: u64,
+
+    /// JSON or TOML file renaming categories in category_names.txt and the --hierarchical headings
+    #[arg(long)]
+    category_map: Option<PathBuf>,
+
+    /// File of category titles, one per line (`#` starts a comment line); only articles of
+    /// these categories are exported
+    #[arg(long)]
+    category_allowlist_file: Option<PathBuf>,
+
+    /// File of category titles, one per line (`#` starts a comment line), left out of the
+    /// export along with the built-in exclusions
+    #[arg(long)]
+    category_denylist_file: Option<PathBuf>,
+
+    /// Drop repeated titles from category_names.txt, keeping the first occurrence
+    #[arg(long)]
+    dedupe_category_names: bool,
+
+    /// Most requests in flight to a single host at once (unbounded when omitted); only
+    /// --validate-links sends several at a time, the crawl itself fetches one by one
+    #[arg(long)]
+    concurrency_per_host: Option<usize>,
+
+    /// Idle connections kept open per host for reuse (reqwest's default when omitted); the crawl
+    /// sends one request at a time, so one is enough for it and only --validate-links uses more
+    #[arg(long)]
+    pool_size: Option<usize>,
+
+    /// Most downloaded files (articles, validators, raw and cached responses, attachments) being
+    /// written to disk at once; no effect for now, as the crawl fetches and writes one at a time
+    #[arg(long, default_value_t = 8, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    max_concurrent_writes: usize,
+
+    /// HTTP/2 use of the shared client: auto leaves it to reqwest's negotiation, prior-knowledge
+    /// speaks HTTP/2 from the first byte (also over plain http), off stays on HTTP/1.1
+    #[arg(long, value_enum, default_value = "auto")]
+    http2: Http2Mode,
+
+    /// Log connection details, such as the HTTP version spoken with each host
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Print no ANSI colors and hand NO_COLOR to --content-transform, same as setting NO_COLOR
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Abort an article download whose body grows beyond this many bytes
+    #[arg(long)]
+    max_article_bytes: Option<u64>,
+
+    /// Fetch this span of article ids directly instead of walking the category tree (e.g. 100..500)
+    #[arg(long, value_parser = parse_id_range)]
+    article_id_range: Option<RangeInclusive<i32>>,
+
+    /// Fetch only the articles listed in <output-dir>/failed.json by the previous crawl, then
+    /// export them with the rest of the cached archive
+    #[arg(long, conflicts_with_all = ["use_local", "source", "article_id_range", "no_cache_write"])]
+    retry_only_failed: bool,
+
+    /// Skip listed articles that answer 404 and list them in final/missing_articles.json,
+    /// instead of counting them as failures; other error statuses still fail
+    #[arg(long)]
+    graceful_404: bool,
+
+    /// Fetch each host's robots.txt first and skip the URLs it disallows for dfu_crawler
+    #[arg(long)]
+    respect_robots: bool,
+
+    /// Fail on any response field the crawler does not know, to notice API changes early; this
+    /// also rejects additions that would otherwise be harmless and simply ignored
+    #[arg(long)]
+    parse_strict: bool,
+
+    /// Order the category tree is crawled in; breadth fetches every shallow article before any
+    /// deeper one, which matters when --max-runtime-secs cuts the crawl short
+    #[arg(long, value_enum, default_value = "depth")]
+    fetch_order: FetchOrder,
+
+    /// Order of category_names.txt; position and id come from the category tree, names it does
+    /// not list keep their place after the others
+    #[arg(long, value_enum, default_value = "traversal")]
+    category_sort: CategorySort,
+
+    /// Shell command every article's contents are piped through before the export, reading the
+    /// new contents from its stdout; DFU_ARTICLE_ID and DFU_LANG name what is being piped
+    #[arg(long)]
+    content_transform: Option<String>,
+
+    /// With --use-local, keep watching <output-dir>/articles and export again whenever cached
+    /// articles change, until Ctrl-C
+    #[arg(long, requires = "use_local", conflicts_with = "sources")]
+    watch: bool,
+
+    /// Stop fetching after this many seconds (per site with --sources) and export what was
+    /// fetched; a later --resume-from or --incremental run picks up the rest
+    #[arg(long)]
+    max_runtime_secs: Option<u64>,
+
+    /// With --use-local, export only articles whose cached file changed after this time
+    /// (2024-05-01 or 2024-05-01T12:00:00+09:00)
+    #[arg(long, value_parser = parse_since, requires = "use_local")]
+    since: Option<SystemTime>,
+
+    /// Export only this many randomly picked articles, for a quick look at the output formats
+    #[arg(long)]
+    sample: Option<usize>,
+
+    /// Seed for --sample so the same articles are picked again
+    #[arg(long, requires = "sample")]
+    seed: Option<u64>,
+
+    /// Title-case the English article and category titles ("The Fall of the City"); the
+    /// other languages are left alone
+    #[arg(long)]
+    title_case: bool,
+
+    /// Export the articles in a random order that this seed reproduces (the flat exports; the
+    /// --hierarchical ones follow the tree); also seeds --sample when --seed is not given
+    #[arg(long)]
+    shuffle_seed: Option<u64>,
+
+    /// Warn about siblings sharing a position (they are ordered by id)
+    #[arg(long)]
+    warn_duplicate_positions: bool,
+
+    /// Do not descend further than this many levels below the top-level categories
+    /// (1 fetches only their direct articles)
+    #[arg(long)]
+    max_depth: Option<usize>,
+
+    /// Fetch only the category listing and write the category exports, skipping every article
+    #[arg(long)]
+    categories_only: bool,
+
+    /// Before the web crawl, check that the category listing and one sample article both load
+    /// and parse, and stop right away with the reason if either does not
+    #[arg(long)]
+    head_check: bool,
+
+    /// Keep categories without any (crawled) article in category_names.txt
+    #[arg(long)]
+    include_empty_categories: bool,
+
+    /// Build category_names.txt from the category titles of the articles instead of the tree,
+    /// and report categories the two disagree on in final/category_discrepancies.csv (or .json)
+    #[arg(long)]
+    categories_from_articles: bool,
+
+    /// Length in characters of the content previews (e.g. the og:description of --article-pages)
+    #[arg(long, default_value_t = 120)]
+    snippet_length: usize,
+
+    /// Abort the crawl at the first article that fails to download
+    #[arg(long, conflicts_with = "keep_going")]
+    fail_fast: bool,
+
+    /// Skip articles that fail to download and report them once the crawl is done (the default)
+    #[arg(long)]
+    keep_going: bool,
+
+    /// Comma separated languages an article needs all of to be exported (e.g. kr,en)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    require_languages: Vec<LangEnum>,
+
+    /// Export only articles with non-blank contents in at least this many languages, whichever
+    /// they are
+    #[arg(long)]
+    content_min_languages: Option<usize>,
+
+    /// Keep only the articles matching an expression such as "status == published && content_len > 500";
+    /// fields are id, status, category, content_len (of the --lang text, markup aside) and
+    /// has_image, combined with ==, !=, <, <=, >, >=, &&, ||, ! and parentheses
+    #[arg(long, value_parser = query::parse)]
+    article_filter_expr: Option<query::Filter>,
+
+    /// Comma separated statuses (e.g. draft,hidden, in any case) whose categories and articles
+    /// are left out; a crawl does not fetch them at all
+    #[arg(long, value_delimiter = ',')]
+    exclude_status: Vec<String>,
+
+    /// Show the contents of every available language, one after another, in each markdown article
+    #[arg(long)]
+    multilang_inline: bool,
+
+    /// Comma separated language order of --multilang-inline
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "kr,en,cn")]
+    multilang_order: Vec<LangEnum>,
+
+    /// Comma separated languages whose title and contents columns CSV rows carry, in this order
+    /// (e.g. kr,en); given no value, every language present in the archive
+    #[arg(long, value_enum, value_delimiter = ',', num_args = 0..)]
+    lang_column_order: Option<Vec<LangEnum>>,
+
+    /// Write markdown article titles in bold instead of wrapping them in ```[...]```
+    #[arg(long)]
+    strip_code_fences: bool,
+
+    /// Directory with Tera templates replacing the built-in rendering: article.html (--article-pages),
+    /// entry.html and entry.md (one article of the combined export). Variables: id, title, subtitle,
+    /// content, category_path, image_url
+    #[arg(long)]
+    template_dir: Option<PathBuf>,
+
+    /// Append every attachment (images inline, anything else as a link) to the markdown and HTML articles
+    #[arg(long)]
+    merge_attachments_into_content: bool,
+
+    /// Add the subtitle to the markdown article headings as `title — subtitle`
+    #[arg(long)]
+    combine_subtitles: bool,
+
+    /// How article contents are read: html is reduced to text for the text exports and embedded as
+    /// markup in the HTML ones, md is taken as it is, auto picks html when the contents contain tags
+    /// (and strips scripts and event handlers before embedding them; html trusts the markup)
+    #[arg(long, value_enum, default_value = "auto")]
+    contents_format: ContentsFormat,
+
+    /// Put the contents of the JSON and CSV exports on one line, line breaks replaced by spaces
+    /// (the default) or by a literal `\n` with `escape`; JSON records then take one line each
+    #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "space")]
+    flatten_contents_newlines: Option<FlattenNewlines>,
+
+    /// Start every markdown article heading with its id, e.g. [12345] Title
+    #[arg(long)]
+    prefix_article_ids: bool,
+
+    /// End with a single key=value line (fetched, failed, excluded, bytes, seconds) instead of the summary
+    #[arg(long)]
+    compact_summary: bool,
+
+    /// Write export files even when nothing is left to put in them; by default an empty
+    /// export is skipped with a warning, and its copy from an earlier run removed
+    #[arg(long)]
+    write_empty_files: bool,
+
+    /// Also write the run summary (counts, failures, timing and the crawl_stats.json fields) as
+    /// JSON to this path, or to stdout for `-`
+    #[arg(long)]
+    summary_json: Option<PathBuf>,
+
+    /// JSON list of articles from earlier exports to merge this crawl's articles into, replacing
+    /// those with the same id and appending new ones; a missing or empty file starts a new list
+    #[arg(long)]
+    merge_into: Option<PathBuf>,
+
+    /// Line endings of the text exports
+    #[arg(long, value_enum, default_value = "lf")]
+    newline: Newline,
+
+    /// Layout of the coverage and doctor reports on the console; json also switches the
+    /// broken-link and category-discrepancy files from CSV to JSON
+    #[arg(long, value_enum, default_value = "table")]
+    report_format: ReportFormat,
+
+    /// Character encoding of the text exports, for older software that misreads BOM-less UTF-8
+    /// (JSON files stay UTF-8)
+    #[arg(long, value_enum, default_value = "utf8")]
+    output_encoding: OutputEncoding,
+
+    /// Group the digits of the counts in the console reports and end them with a timestamp,
+    /// ISO 8601 for en and the Korean date order for ko
+    #[arg(long, value_enum)]
+    locale: Option<Locale>,
+
+    /// Directory that holds the cached responses and the final exports. Placeholders expand to
+    /// the local time the run started: {date} (2024-01-31), {time} (13-05-09), {datetime}
+    /// (2024-01-31_13-05-09), or any strftime format in braces, e.g. {%Y%m}
+    #[arg(long, default_value = "crawled_data")]
+    output_dir: PathBuf,
+
+    /// Create a missing --output-dir (and its missing parents) without asking
+    #[arg(short, long)]
+    yes: bool,
+
+    /// URL of the category tree of the site to crawl
+    #[arg(long, default_value = DEFAULT_CATEGORIES_URL)]
+    categories_url: String,
+
+    /// Article endpoint of the site to crawl; `{}` is replaced by the article id, otherwise the id is appended
+    #[arg(long, default_value = DEFAULT_ARTICLE_BASE_URL)]
+    article_base_url: String,
+
+    /// Public page of an article with an {id} placeholder, used for the canonical links of the
+    /// HTML exports instead of the API URL they are fetched from
+    #[arg(long, value_parser = parse_url_template)]
+    article_url_template: Option<String>,
+
+    /// JSON list of sites ({name, categories_url, article_base_url}), each crawled into <output-dir>/<name>
+    #[arg(long)]
+    sources: Option<PathBuf>,
+
+    /// Re-export from a single saved file instead of crawling (json:<path> for an all_articles.json,
+    /// ndjson:<path> for one article per line)
+    #[arg(long, value_parser = parse_input_source, conflicts_with_all = ["use_local", "article_id_range", "sources"])]
+    source: Option<InputSource>,
+}
+
+const DEFAULT_CATEGORIES_URL: &str = "https://static.dnf-universe.com/categories.json";
+const DEFAULT_ARTICLE_BASE_URL: &str = "https://www.dnf-universe.com/api/v1/story/{}";
+
+#[derive(Debug, Deserialize)]
+struct Source {
+    name: String,
+    categories_url: String,
+    article_base_url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OverwritePolicy {
+    /// Leave the existing file untouched
+    Skip,
+    Overwrite,
+    /// Rename the existing file with a timestamp before writing
+    Backup,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Newline {
+    Lf,
+    Crlf,
+}
+
+impl Newline {
+    // Line breaks that already are CRLF (e.g. inside article contents) are kept as they are
+    fn apply(self, body: &[u8]) -> std::borrow::Cow<'_, [u8]> {
+        if self == Newline::Lf {
+            return body.into();
+        }
+
+        let mut converted = Vec::with_capacity(body.len());
+        for (i, &byte) in body.iter().enumerate() {
+            if byte == b'\n' && (i == 0 || body[i - 1] != b'\r') {
+                converted.push(b'\r');
+            }
+            converted.push(byte);
+        }
+
+        converted.into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AttachmentNameFrom {
+    Id,
+    Url,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Http2Mode {
+    Auto,
+    PriorKnowledge,
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FetchOrder {
+    Depth,
+    Breadth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CategorySort {
+    Traversal,
+    Position,
+    Title,
+    Id,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Locale {
+    En,
+    Ko,
+}
+
+impl Locale {
+    fn timestamp(self, time: chrono::DateTime<chrono::Local>) -> String {
+        match self {
+            Locale::En => time.format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+            Locale::Ko => time.format("%Y. %m. %d. %H:%M:%S").to_string(),
+        }
+    }
+}
+
+// 1234567 -> 1,234,567 with a --locale (both group by thousands), unchanged without one
+fn format_count(locale: Option<Locale>, value: impl std::fmt::Display) -> String {
+    let digits = value.to_string();
+    if locale.is_none() {
+        return digits;
+    }
+
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputEncoding {
+    Utf8,
+    Utf8Bom,
+    EucKr,
+}
+
+impl OutputEncoding {
+    // EUC-KR has no room for some characters (e.g. emoji), those become numeric character references;
+    // JSON is UTF-8 by definition (RFC 8259), so it is left as it is
+    fn encode(self, body: &[u8], json: bool) -> anyhow::Result<std::borrow::Cow<'_, [u8]>> {
+        match self {
+            _ if json => Ok(body.into()),
+            OutputEncoding::Utf8 => Ok(body.into()),
+            OutputEncoding::Utf8Bom => Ok([&b"\xEF\xBB\xBF"[..], body].concat().into()),
+            OutputEncoding::EucKr => {
+                let text = std::str::from_utf8(body)?;
+                let (encoded, _, unmappable) = encoding_rs::EUC_KR.encode(text);
+
+                if unmappable {
+                    eprintln!("Some characters have no EUC-KR form and were written as &#NNNN; references");
+                }
+
+                Ok(encoded.into_owned().into())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Checkpoint {
+    last_article_id: i32,
+}
+
+// One line of the log next to the checkpoint: what a category added once its whole subtree was
+// crawled, so a resumed crawl restores it without descending into it again
+#[derive(Debug, Deserialize, Serialize)]
+struct FinishedCategory {
+    id: i32,
+    category_names: Vec<String>,
+    article_ids: Vec<i32>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Browse the local archive in an interactive terminal UI
+    Browse,
+    /// Print per language how many local articles have contents and how long they are on average
+    Coverage {
+        /// Count contents byte-identical to another language's as untranslated, and list them
+        #[arg(long)]
+        identical_as_untranslated: bool,
+    },
+    /// Download the attachments (and their thumbnails) of the already crawled local articles
+    DownloadAttachments,
+    /// Check the output directory, the cached data and whether the site is reachable
+    Doctor,
+    /// Write a synthetic archive of schema-valid articles into <output-dir>, for tests and benchmarks
+    #[command(hide = true)]
+    GenFixtures {
+        /// How many articles to generate
+        #[arg(long)]
+        count: usize,
+
+        /// The same seed generates the same archive
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Delete cached articles that the current category tree no longer lists
+    Prune {
+        /// Only list the files that would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write JSON Schemas of the exported articles and the category listing into <output-dir>/schema
+    Schema,
+    /// Serve the local archive as a browsable site on localhost, without writing any files
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Write the most frequent tokens of the local articles in --lang to a CSV
+    Wordfreq {
+        /// How many tokens to keep
+        #[arg(long, default_value_t = 100)]
+        top: usize,
+
+        /// File with one stopword per line to leave out of the counts
+        #[arg(long)]
+        stopwords: Option<PathBuf>,
+    },
+}
+
+struct Crawler {
+    args: Arguments,
+    client: reqwest::Client,
+    rate_limiter: DefaultDirectRateLimiter,
+    ignored_ids: HashSet<i32>,
+    retry_stats: RetryStats,
+    fetch_times: Mutex<Vec<Duration>>,
+    host_permits: Mutex<HashMap<String, Arc<Semaphore>>>,
+    write_permits: Semaphore,
+    // With --verbose, the hosts whose HTTP version was already logged
+    logged_versions: Mutex<HashSet<String>>,
+    deadline: Option<tokio::time::Instant>,
+    // With --incremental, content_hashes.json from the previous crawl
+    stored_hashes: HashMap<i32, String>,
+    // robots.txt bodies by origin, fetched on the first request to each with --respect-robots
+    robots: tokio::sync::Mutex<HashMap<String, String>>,
+}
+
+// Counted in get_page_content, the single place that retries
+#[derive(Debug, Default, Serialize)]
+struct RetryStats {
+    retries: AtomicUsize,
+    succeeded_after_retry: AtomicUsize,
+    failed_permanently: AtomicUsize,
+}
+
+impl RetryStats {
+    fn print(&self, locale: Option<Locale>) {
+        println!(
+            "{} requests succeeded after retry, {} failed permanently ({} retries in total)",
+            format_count(locale, self.succeeded_after_retry.load(Ordering::Relaxed)),
+            format_count(locale, self.failed_permanently.load(Ordering::Relaxed)),
+            format_count(locale, self.retries.load(Ordering::Relaxed))
+        );
+    }
+}
+
+// How long the get_article_content calls took, in milliseconds; waits for the --rps limiter
+// count too, so compare the spread rather than the absolute numbers
+#[derive(Debug, Serialize)]
+struct FetchTimings {
+    count: usize,
+    min_ms: u128,
+    median_ms: u128,
+    p95_ms: u128,
+    max_ms: u128,
+}
+
+impl FetchTimings {
+    fn from_durations(durations: &[Duration]) -> Option<Self> {
+        let mut millis = durations
+            .iter()
+            .map(Duration::as_millis)
+            .collect::<Vec<_>>();
+        millis.sort_unstable();
+
+        // Nearest-rank percentile
+        let percentile = |p: usize| millis[(millis.len() * p).div_ceil(100).max(1) - 1];
+
+        Some(Self {
+            count: millis.len(),
+            min_ms: *millis.first()?,
+            median_ms: percentile(50),
+            p95_ms: percentile(95),
+            max_ms: *millis.last()?,
+        })
+    }
+
+    fn print(&self, locale: Option<Locale>) {
+        println!(
+            "{} article fetches: min {} ms, median {} ms, p95 {} ms, max {} ms",
+            format_count(locale, self.count),
+            format_count(locale, self.min_ms),
+            format_count(locale, self.median_ms),
+            format_count(locale, self.p95_ms),
+            format_count(locale, self.max_ms)
+        );
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CrawlStats<'a> {
+    #[serde(flatten)]
+    retries: &'a RetryStats,
+    article_fetches: Option<FetchTimings>,
+    // RFC 3339 whatever the --locale, the file is for scripts
+    finished_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RunSummary<'a> {
+    fetched: usize,
+    written: usize,
+    excluded: usize,
+    skipped_missing_lang: usize,
+    skipped_incomplete: usize,
+    skipped_few_languages: usize,
+    bytes_written: u64,
+    failed: &'a [String],
+    elapsed_secs: f64,
+    #[serde(flatten)]
+    stats: &'a CrawlStats<'a>,
+}
+
+#[derive(Debug, Clone)]
+pub enum InputSource {
+    Json(PathBuf),
+    Ndjson(PathBuf),
+}
+
+fn parse_input_source(value: &str) -> Result<InputSource, String> {
+    match value.split_once(':') {
+        Some(("json", path)) if !path.is_empty() => Ok(InputSource::Json(PathBuf::from(path))),
+        Some(("ndjson" | "jsonl", path)) if !path.is_empty() => {
+            Ok(InputSource::Ndjson(PathBuf::from(path)))
+        }
+        _ => Err(format!(
+            "expected json:<path> or ndjson:<path>, got {}",
+            value
+        )),
+    }
+}
+
+impl Arguments {
+    // Clap colors the help and errors it prints while parsing, so the flag is looked for up front;
+    // NO_COLOR and output that is not a terminal are already honored by clap itself
+    pub fn parse_args() -> Self {
+        let no_color = std::env::args_os()
+            .take_while(|arg| arg != "--")
+            .any(|arg| arg == "--no-color");
+
+        let mut command = Self::command();
+        if no_color {
+            command = command.color(ColorChoice::Never);
+        }
+
+        Self::from_arg_matches(&command.get_matches()).unwrap_or_else(|e| e.exit())
+    }
+
+    // Reads data already on disk, so neither the site nor the retry stats are involved
+    fn reads_offline(&self) -> bool {
+        self.use_local || self.source.is_some()
+    }
+
+    fn writes_to_stdout(&self) -> bool {
+        self.stdout || self.output_file.as_deref() == Some(Path::new("-"))
+    }
+
+    // `count` is the 1-based number of the article about to be printed
+    fn prints_progress(&self, count: usize) -> bool {
+        !self.writes_to_stdout() && (count as u64).is_multiple_of(self.progress_every)
+    }
+
+    fn output_formats(&self) -> anyhow::Result<Vec<OutputFormat>> {
+        if !self.output_format.is_empty() {
+            return Ok(self.output_format.clone());
+        }
+
+        match &self.output_file {
+            Some(path) if !self.writes_to_stdout() => Ok(vec![OutputFormat::from_path(path)?]),
+            _ => Ok(vec![OutputFormat::Md]),
+        }
+    }
+
+    // --keep-going is the default and only spelled out for clarity, --fail-fast turns it off
+    fn keeps_going(&self) -> bool {
+        self.keep_going || !self.fail_fast
+    }
+
+    fn title_lang(&self) -> &LangEnum {
+        self.category_title_lang.as_ref().unwrap_or(&self.lang)
+    }
+
+    fn selected_fields(&self) -> anyhow::Result<Vec<&'static str>> {
+        self.select_fields
+            .iter()
+            .map(|name| export::parse_field(name))
+            .collect()
+    }
+
+    // The public page of an article, as opposed to the API endpoint it is fetched from
+    fn canonical_url(&self, id: i32) -> Option<String> {
+        self.article_url_template
+            .as_ref()
+            .map(|template| export::fill_url_template(template, id))
+    }
+
+    fn inline_langs(&self) -> Vec<LangEnum> {
+        match self.multilang_inline {
+            true => self.multilang_order.clone(),
+            false => vec![],
+        }
+    }
+
+    // Original title -> display title, read as TOML for a .toml file and as JSON otherwise
+    fn category_map(&self) -> anyhow::Result<HashMap<String, String>> {
+        let Some(path) = &self.category_map else {
+            return Ok(HashMap::new());
+        };
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", path.display(), e))?;
+        let map: HashMap<String, String> = if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&content)?
+        } else {
+            serde_json::from_str(&content)?
+        };
+
+        Ok(map
+            .into_iter()
+            .map(|(original, display)| (normalize_title(&original), display))
+            .collect())
+    }
+
+    // Up front, as reqwest only reports a bad URL at the first request and in its own words
+    fn check_site_urls(&self) -> anyhow::Result<()> {
+        check_url("--categories-url", &self.categories_url)?;
+
+        let base_url = &self.article_base_url;
+        anyhow::ensure!(
+            !base_url.replace("{}", "").contains(['{', '}']),
+            "invalid URL for --article-base-url: {}, the only placeholder is {{}} for the article id",
+            base_url
+        );
+        check_url("--article-base-url", &article_url(base_url, 1))
+    }
+
+    fn excludes_status(&self, status: &str) -> bool {
+        self.exclude_status
+            .iter()
+            .any(|excluded| excluded.eq_ignore_ascii_case(status))
+    }
+
+    fn category_allowlist(&self) -> anyhow::Result<Option<HashSet<String>>> {
+        self.category_allowlist_file
+            .as_deref()
+            .map(load_category_list)
+            .transpose()
+    }
+
+    fn category_denylist(&self) -> anyhow::Result<HashSet<String>> {
+        match &self.category_denylist_file {
+            Some(path) => load_category_list(path),
+            None => Ok(HashSet::new()),
+        }
+    }
+}
+
+fn client_builder(args: &Arguments) -> reqwest::ClientBuilder {
+    let mut builder = reqwest::Client::builder();
+
+    if let Some(pool_size) = args.pool_size {
+        builder = builder.pool_max_idle_per_host(pool_size);
+    }
+
+    builder = match args.http2 {
+        Http2Mode::Auto => builder,
+        Http2Mode::PriorKnowledge => builder.http2_prior_knowledge(),
+        Http2Mode::Off => builder.http1_only(),
+    };
+
+    builder
+}
+
+impl Crawler {
+    fn new(args: &Arguments, ignored_ids: HashSet<i32>) -> anyhow::Result<Self> {
+        anyhow::ensure!(args.rps > 0.0, "--rps must be greater than zero");
+
+        // A tiny --rps makes the period overflow a Duration, which from_secs_f64 panics on
+        let quota = Duration::try_from_secs_f64(1.0 / args.rps)
+            .ok()
+            .and_then(Quota::with_period)
+            .ok_or_else(|| anyhow::anyhow!("Invalid --rps value: {:?}", args.rps))?;
+
+        Ok(Self {
+            args: args.clone(),
+            client: client_builder(args).build()?,
+            rate_limiter: RateLimiter::direct(quota),
+            ignored_ids,
+            retry_stats: RetryStats::default(),
+            fetch_times: Mutex::new(vec![]),
+            host_permits: Mutex::new(HashMap::new()),
+            write_permits: Semaphore::new(args.max_concurrent_writes),
+            logged_versions: Mutex::new(HashSet::new()),
+            robots: tokio::sync::Mutex::new(HashMap::new()),
+            stored_hashes: match args.incremental {
+                true => load_content_hashes(&args.output_dir)?.unwrap_or_default(),
+                false => HashMap::new(),
+            },
+            deadline: args
+                .max_runtime_secs
+                .map(|secs| tokio::time::Instant::now() + Duration::from_secs(secs)),
+        })
+    }
+}
+
+pub struct CrawlData {
+    categories: Vec<CategoryChildResponse>,
+    category_names: Vec<String>,
+    ko_articles: Vec<ArticleDataResponse>,
+    errors: Vec<String>,
+}
+
+// Everything a traversal accumulates on its way down the category tree
+struct CrawlState {
+    category_names: Vec<String>,
+    ko_articles: Vec<ArticleDataResponse>,
+    // Articles up to (and including) this id were fetched by the interrupted run
+    resume_after: Option<i32>,
+    // Categories the interrupted run finished, by id
+    finished: HashMap<i32, FinishedCategory>,
+    // Articles known to be fetched in full, appended to completed.jsonl after each one
+    completed: HashSet<i32>,
+    errors: Vec<String>,
+    // With --graceful-404, listed articles that answered 404
+    missing_articles: Vec<i32>,
+    // The ids behind `errors`, for --retry-only-failed
+    failed_ids: Vec<i32>,
+    seen_articles: HashSet<i32>,
+    // With --fetch-order breadth, what the breadth-first pass fetched, to be replayed in tree order
+    prefetched: Option<HashMap<i32, ArticleDataResponse>>,
+}
+
+async fn read_from_web(crawler: &Crawler) -> anyhow::Result<CrawlData> {
+    let categories = match crawler.args.head_check {
+        true => head_check(crawler).await?,
+        false => get_category_response(crawler, &crawler.args.categories_url).await?,
+    };
+    let roots = select_roots(&crawler.args, &categories.data)?;
+
+    let (resume_after, finished) = match &crawler.args.resume_from {
+        Some(path) => {
+            let checkpoint: Checkpoint = serde_json::from_reader(File::open(path)?)?;
+            let finished = load_finished_categories(&finished_categories_path(path))?;
+            println!(
+                "Resuming after article {}, skipping {} finished categories",
+                checkpoint.last_article_id,
+                format_count(crawler.args.locale, finished.len())
+            );
+
+            (Some(checkpoint.last_article_id), finished)
+        }
+        None => {
+            // A log left by an earlier crawl describes another traversal
+            let log_path = finished_categories_path(&checkpoint_path(&crawler.args.output_dir));
+            if log_path.exists() {
+                std::fs::remove_file(log_path)?;
+            }
+
+            (None, HashMap::new())
+        }
+    };
+
+    let completed = if crawler.args.incremental {
+        load_completed(&crawler.args.output_dir)?
+    } else {
+        HashSet::new()
+    };
+
+    let mut state = CrawlState {
+        category_names: vec![],
+        ko_articles: vec![],
+        resume_after,
+        finished,
+        completed,
+        errors: vec![],
+        missing_articles: vec![],
+        failed_ids: vec![],
+        seen_articles: HashSet::new(),
+        prefetched: None,
+    };
+    let mut root_counts = vec![];
+
+    let traversal = async {
+        match crawler.args.fetch_order {
+            FetchOrder::Depth => iterate_roots(crawler, roots, &mut state, &mut root_counts).await,
+            FetchOrder::Breadth => iterate_breadth_first(crawler, roots, &mut state).await,
+        }
+    };
+
+    // Ctrl-C drops the in-flight fetch at its next await; cached files are only ever
+    // renamed into place whole, and the checkpoint still names the last complete article
+    let deadline = async {
+        match crawler.deadline {
+            Some(deadline) => tokio::time::sleep_until(deadline).await,
+            None => std::future::pending().await,
+        }
+    };
+
+    // The deadline stops the traversal the same way, but what was fetched so far is still exported
+    let mut out_of_time = false;
+    tokio::select! {
+        result = traversal => result?,
+        _ = deadline => out_of_time = true,
+        _ = tokio::signal::ctrl_c() => {
+            anyhow::bail!(
+                "Interrupted; continue with --resume-from {}",
+                checkpoint_path(&crawler.args.output_dir).display()
+            );
+        }
+    }
+
+    // The depth-first walk over what was fetched yields the same category names, counts and
+    // article order as a depth-first crawl would have
+    if crawler.args.fetch_order == FetchOrder::Breadth {
+        let fetched = std::mem::take(&mut state.ko_articles);
+        state.prefetched = Some(
+            fetched
+                .into_iter()
+                .map(|article| (article.id, article))
+                .collect(),
+        );
+        state.seen_articles.clear();
+
+        iterate_roots(crawler, roots, &mut state, &mut root_counts).await?;
+    }
+
+    if crawler.args.pretty_progress && !crawler.args.writes_to_stdout() {
+        print_category_table(&root_counts, crawler.args.locale);
+    }
+
+    write_failed(crawler, &state.failed_ids)?;
+    if !crawler.args.no_cache_write {
+        write_completed(&crawler.args.output_dir, &state.completed)?;
+    }
+
+    if crawler.args.graceful_404 {
+        state.missing_articles.sort_unstable();
+
+        let final_dir = crawler.args.output_dir.join("final");
+        std::fs::create_dir_all(&final_dir).unwrap();
+        write_atomic(
+            &final_dir.join("missing_articles.json"),
+            &serde_json::to_vec(&state.missing_articles)?,
+        )?;
+    }
+
+    let checkpoint_path = checkpoint_path(&crawler.args.output_dir);
+    if out_of_time {
+        report_deadline(
+            crawler,
+            state.ko_articles.len(),
+            &format!("continue with --resume-from {}", checkpoint_path.display()),
+        );
+    } else {
+        // The crawl completed, so there is nothing left to resume
+        for path in [finished_categories_path(&checkpoint_path), checkpoint_path] {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+    }
+
+    Ok(CrawlData {
+        categories: roots.to_vec(),
+        category_names: state.category_names,
+        ko_articles: state.ko_articles,
+        errors: state.errors,
+    })
+}
+
+fn report_deadline(crawler: &Crawler, fetched: usize, next_step: &str) {
+    eprintln!(
+        "Stopped at the --max-runtime-secs deadline after {} articles; {}",
+        format_count(crawler.args.locale, fetched),
+        next_step
+    );
+}
+
+// Backfill mode: fetches every id of the range directly, the category tree is never consulted
+async fn read_id_range(crawler: &Crawler, range: RangeInclusive<i32>) -> anyhow::Result<CrawlData> {
+    let mut category_names = vec![];
+    let mut ko_articles = vec![];
+    let mut errors = vec![];
+    let mut missing_ids = vec![];
+    let mut failed_ids = vec![];
+
+    let end = *range.end();
+    for id in range {
+        if crawler.ignored_ids.contains(&id) {
+            continue;
+        }
+
+        if crawler
+            .deadline
+            .is_some_and(|deadline| tokio::time::Instant::now() >= deadline)
+        {
+            report_deadline(
+                crawler,
+                ko_articles.len(),
+                &format!("continue with --article-id-range {}..={}", id, end),
+            );
+            break;
+        }
+
+        let mut article = match get_article_content(crawler, id).await {
+            Ok(article) => article,
+            Err(e) if is_not_found(&e) => {
+                missing_ids.push(id.to_string());
+                continue;
+            }
+            Err(e) if e.is::<DisallowedByRobots>() => {
+                eprintln!("Skipping article {}, disallowed by robots.txt", id);
+                continue;
+            }
+            Err(e) if !crawler.args.keeps_going() => return Err(e),
+            Err(e) => {
+                errors.push(format!("article {}: {:#}", id, e));
+                failed_ids.push(id);
+                continue;
+            }
+        };
+        normalize_article_titles(&mut article.data);
+
+        // Unlike the listed articles, nothing vouches for an id picked from a span; the export
+        // files every article under its Korean category
+        let Some(category_title) = article.data.category_titles.get(&LangEnum::KR).cloned() else {
+            anyhow::ensure!(
+                crawler.args.keeps_going(),
+                "article {} has no Korean category title",
+                id
+            );
+            errors.push(format!("article {}: no Korean category title", id));
+            failed_ids.push(id);
+            continue;
+        };
+        if !category_names.contains(&category_title) {
+            category_names.push(category_title.clone());
+        }
+
+        if crawler.args.prints_progress(ko_articles.len() + 1) {
+            println!(
+                "{} - {}",
+                category_title,
+                localized(&article.data.titles, &LangEnum::KR)
+            );
+        }
+
+        ko_articles.push(article.data);
+    }
+
+    if !missing_ids.is_empty() {
+        eprintln!("No article for ids {}", missing_ids.join(", "));
+    }
+
+    write_failed(crawler, &failed_ids)?;
+
+    Ok(CrawlData {
+        categories: vec![],
+        category_names,
+        ko_articles,
+        errors,
+    })
+}
+
+// `100..500` excludes the end like a Rust range, `100..=500` includes it
+fn parse_id_range(value: &str) -> Result<RangeInclusive<i32>, String> {
+    let (start, end, inclusive) = match value.split_once("..=") {
+        Some((start, end)) => (start, end, true),
+        None => {
+            let (start, end) = value
+                .split_once("..")
+                .ok_or_else(|| format!("expected START..END, got {}", value))?;
+            (start, end, false)
+        }
+    };
+
+    let start = start.trim().parse::<i32>().map_err(|e| e.to_string())?;
+    let end = end.trim().parse::<i32>().map_err(|e| e.to_string())?;
+
+    // Nothing lies below i32::MIN, so an exclusive end there is as empty as 5..5
+    let range = match inclusive {
+        true => Some(start..=end),
+        false => end.checked_sub(1).map(|end| start..=end),
+    };
+
+    match range {
+        Some(range) if !range.is_empty() => Ok(range),
+        _ => Err(format!(
+            "{} is empty, START has to be below END (or equal to it with ..=)",
+            value
+        )),
+    }
+}
+
+fn parse_url_template(value: &str) -> Result<String, String> {
+    match value.contains("{id}") {
+        true => Ok(value.to_string()),
+        false => Err(format!("expected an {{id}} placeholder in {}", value)),
+    }
+}
+
+// A bare date means its midnight in local time
+fn parse_since(value: &str) -> Result<SystemTime, String> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(time.into());
+    }
+
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        format!(
+            "expected YYYY-MM-DD or an RFC 3339 timestamp, got {}",
+            value
+        )
+    })?;
+
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|time| time.and_local_timezone(chrono::Local).earliest())
+        .map(SystemTime::from)
+        .ok_or_else(|| format!("{} does not exist in the local time zone", value))
+}
+
+pub async fn read_from_local(args: &Arguments) -> anyhow::Result<CrawlData> {
+    let ko_articles_path = args.output_dir.join("articles");
+
+    if !ko_articles_path.is_dir() {
+        anyhow::bail!("No local data found; run a crawl first (omit --use-local)");
+    }
+
+    let categories_path = args.output_dir.join("category").join("categories.json");
+
+    if !categories_path.exists() {
+        anyhow::ensure!(
+            args.category_id.is_none(),
+            "--category-id needs the category listing at {}",
+            categories_path.display()
+        );
+
+        eprintln!(
+            "{} not found, deriving the category names from the cached articles",
+            categories_path.display()
+        );
+
+        let ko_articles = load_local_articles(&ko_articles_path, args.since)?;
+
+        return Ok(CrawlData {
+            categories: vec![],
+            category_names: category_names_from_articles(&ko_articles),
+            ko_articles,
+            errors: vec![],
+        });
+    }
+
+    let category_names = std::fs::read_to_string(&categories_path)?;
+    let mut category_response: CategoryResponse = probe::parse(&category_names)?;
+    if category_response.data.is_empty() {
+        eprintln!(
+            "{} lists no categories, the crawl that cached it likely got a bad response",
+            categories_path.display()
+        );
+    }
+    normalize_category_titles(&mut category_response.data);
+    sort_by_position(args, &mut category_response.data, None);
+    let roots = select_roots(args, &category_response.data)?;
+
+    let mut article_ids = HashSet::new();
+    collect_article_ids(roots, &mut article_ids);
+
+    let ko_articles = load_local_articles(&ko_articles_path, args.since)?
+        .into_iter()
+        .filter(|article| args.category_id.is_none() || article_ids.contains(&article.id))
+        .collect::<Vec<_>>();
+
+    let loaded_ids = ko_articles
+        .iter()
+        .map(|article| article.id)
+        .collect::<HashSet<_>>();
+
+    Ok(CrawlData {
+        categories: roots.to_vec(),
+        category_names: roots
+            .iter()
+            .filter(|child| child.type_ == "CATEGORY")
+            .filter(|child| {
+                let mut subtree_ids = HashSet::new();
+                collect_article_ids(&child.children, &mut subtree_ids);
+
+                args.include_empty_categories || !subtree_ids.is_disjoint(&loaded_ids)
+            })
+            .map(|child| child.titles[&LangEnum::KR].clone())
+            .collect(),
+        ko_articles,
+        errors: vec![],
+    })
+}
+
+// Re-reads a combined JSON export; without a category listing the names come from the articles
+pub fn read_from_json(path: &Path) -> anyhow::Result<CrawlData> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Cannot open {}: {}", path.display(), e))?;
+
+    let mut ko_articles: Vec<ArticleDataResponse> = probe::parse(&content).map_err(|e| {
+        anyhow::anyhow!(
+            "{} is not a list of complete articles (was it exported with --select-fields?): {}",
+            path.display(),
+            e
+        )
+    })?;
+    ko_articles.iter_mut().for_each(normalize_article_titles);
+
+    Ok(CrawlData {
+        categories: vec![],
+        category_names: category_names_from_articles(&ko_articles),
+        ko_articles,
+        errors: vec![],
+    })
+}
+
+// Parses one line at a time, so only the articles are held in memory and never the file text
+// as well; the export still needs all of them at once. A malformed line is skipped with a
+// warning instead of failing the export
+pub fn read_from_ndjson(path: &Path) -> anyhow::Result<CrawlData> {
+    let file =
+        File::open(path).map_err(|e| anyhow::anyhow!("Cannot open {}: {}", path.display(), e))?;
+
+    let mut ko_articles = vec![];
+    for (line_number, line) in std::io::BufRead::lines(std::io::BufReader::new(file)).enumerate() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match probe::parse::<ArticleDataResponse>(&line) {
+            Ok(mut article) => {
+                normalize_article_titles(&mut article);
+                ko_articles.push(article);
+            }
+            Err(e) => eprintln!(
+                "Skipping line {} of {}: {}",
+                line_number + 1,
+                path.display(),
+                e
+            ),
+        }
+    }
+
+    Ok(CrawlData {
+        categories: vec![],
+        category_names: category_names_from_articles(&ko_articles),
+        ko_articles,
+        errors: vec![],
+    })
+}
+
+// Category titles in the order their first article appears
+fn category_names_from_articles(articles: &[ArticleDataResponse]) -> Vec<String> {
+    let mut category_names = vec![];
+
+    for article in articles {
+        let title = &article.category_titles[&LangEnum::KR];
+
+        if !category_names.contains(title) {
+            category_names.push(title.clone());
+        }
+    }
+
+    category_names
+}
+
+fn load_local_articles(
+    ko_articles_path: &Path,
+    since: Option<SystemTime>,
+) -> anyhow::Result<Vec<ArticleDataResponse>> {
+    let paths = std::fs::read_dir(ko_articles_path)?
+        .map(|entry| entry.unwrap())
+        // Checked before parsing, so old files cost a stat rather than a read
+        .filter(|entry| {
+            since.is_none_or(|since| {
+                entry
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .is_ok_and(|modified| modified > since)
+            })
+        })
+        .map(|entry| entry.path())
+        // Leftover `.tmp` files are writes that never completed
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect::<Vec<_>>();
+
+    // Reading and parsing spread over the rayon pool, which finishes in no particular order
+    let mut ko_articles = paths
+        .par_iter()
+        .map(|path| {
+            let content = std::fs::read_to_string(path)?;
+
+            let mut article: ArticleResponse =
+                probe::parse(&content).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+            normalize_article_titles(&mut article.data);
+            Ok(article.data)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    ko_articles.sort_by_key(|article| article.id);
+
+    Ok(ko_articles)
+}
+
+fn print_category_table(rows: &[(String, usize)], locale: Option<Locale>) {
+    let total = rows.iter().map(|(_, count)| count).sum::<usize>();
+    let total_row = ("Total".to_string(), total);
+
+    let title_width = rows
+        .iter()
+        .chain(std::iter::once(&total_row))
+        .map(|(title, _)| title.width())
+        .max()
+        .unwrap_or(0)
+        .max("Category".len());
+    let count_width = format_count(locale, total).len().max("Articles".len());
+
+    let separator = format!(
+        "+-{}-+-{}-+",
+        "-".repeat(title_width),
+        "-".repeat(count_width)
+    );
+    let print_row = |title: &str, count: &str| {
+        // Pad by display width, `{:<w$}` would count double-width glyphs as one column
+        let padding = " ".repeat(title_width - title.width());
+        println!("| {}{} | {:>count_width$} |", title, padding, count);
+    };
+
+    println!("{}", separator);
+    print_row("Category", "Articles");
+    println!("{}", separator);
+    for (title, count) in rows {
+        print_row(title, &format_count(locale, count));
+    }
+    println!("{}", separator);
+    print_row(&total_row.0, &format_count(locale, total));
+    println!("{}", separator);
+}
+
+// With --category-id the chosen category itself is the one root, so its own name is kept
+fn select_roots<'a>(
+    args: &Arguments,
+    data: &'a [CategoryChildResponse],
+) -> anyhow::Result<&'a [CategoryChildResponse]> {
+    match args.category_id {
+        Some(id) => find_category(data, id)
+            .map(std::slice::from_ref)
+            .ok_or_else(|| anyhow::anyhow!("Category id {} not found in the category tree", id)),
+        None => Ok(data),
+    }
+}
+
+fn find_category(children: &[CategoryChildResponse], id: i32) -> Option<&CategoryChildResponse> {
+    children.iter().find_map(|child| {
+        if child.id == id && child.type_ == "CATEGORY" {
+            Some(child)
+        } else {
+            find_category(&child.children, id)
+        }
+    })
+}
+
+fn collect_article_ids(children: &[CategoryChildResponse], ids: &mut HashSet<i32>) {
+    for child in children {
+        if child.type_ == "ARTICLE" {
+            ids.insert(child.id);
+        }
+
+        collect_article_ids(&child.children, ids);
+    }
+}
+
+// Normalized KR title (the key category_names.txt uses) -> the rank of the first category node
+// carrying it: its id, or its place in the tree once every parent's children are in position order
+fn collect_category_ranks(
+    children: &[CategoryChildResponse],
+    sort: CategorySort,
+    ranks: &mut HashMap<String, i64>,
+) {
+    let mut siblings = children.iter().collect::<Vec<_>>();
+    if sort == CategorySort::Position {
+        siblings.sort_by_key(|child| (child.position, child.id));
+    }
+
+    for child in siblings {
+        if child.type_ == "CATEGORY" {
+            if let Some(title) = child.titles.get(&LangEnum::KR) {
+                let rank = match sort {
+                    CategorySort::Position => ranks.len() as i64,
+                    _ => child.id as i64,
+                };
+                ranks.entry(normalize_title(title)).or_insert(rank);
+            }
+        }
+
+        collect_category_ranks(&child.children, sort, ranks);
+    }
+}
+
+pub async fn post_process(
+    args: &Arguments,
+    data: &CrawlData,
+    exclude_categories: &[String],
+    ignored_ids: &HashSet<i32>,
+) -> anyhow::Result<ProcessSummary> {
+    // Everything is written into final.tmp first and only swapped in once complete, so a crash
+    // mid-export never leaves a final/ that looks valid but is half written
+    let final_dir = args.output_dir.join("final");
+    let staging_dir = args.output_dir.join("final.tmp");
+
+    stage_dir(&final_dir, &staging_dir)?;
+
+    let mut summary = write_exports(args, data, exclude_categories, ignored_ids, &staging_dir)?;
+
+    swap_dir(&staging_dir, &final_dir)?;
+    summary.relocate(&staging_dir, &final_dir);
+
+    Ok(summary)
+}
+
+// Seeds the staging dir with the current exports, so files this run does not produce survive
+// and the overwrite policy still sees what is already there
+fn stage_dir(final_dir: &Path, staging_dir: &Path) -> anyhow::Result<()> {
+    if staging_dir.exists() {
+        std::fs::remove_dir_all(staging_dir)?;
+    }
+
+    if final_dir.is_dir() {
+        copy_dir(final_dir, staging_dir)?;
+    } else {
+        std::fs::create_dir_all(staging_dir).unwrap();
+    }
+
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> anyhow::Result<()> {
+    std::fs::create_dir_all(to).unwrap();
+
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Neither Windows nor Unix (for a non-empty target) renames a directory over an existing one,
+// so the old exports are moved aside first and only removed once the new ones are in place
+fn swap_dir(staging_dir: &Path, final_dir: &Path) -> anyhow::Result<()> {
+    let old_dir = final_dir.with_file_name("final.old");
+
+    if old_dir.exists() {
+        std::fs::remove_dir_all(&old_dir)?;
+    }
+
+    if final_dir.exists() {
+        std::fs::rename(final_dir, &old_dir)?;
+    }
+
+    std::fs::rename(staging_dir, final_dir)?;
+
+    if old_dir.exists() {
+        std::fs::remove_dir_all(&old_dir)?;
+    }
+
+    Ok(())
+}
+
+fn write_exports(
+    args: &Arguments,
+    data: &CrawlData,
+    exclude_categories: &[String],
+    ignored_ids: &HashSet<i32>,
+    final_dir: &Path,
+) -> anyhow::Result<ProcessSummary> {
+    let CrawlData {
+        categories,
+        category_names,
+        ko_articles,
+        ..
+    } = data;
+
+    let output_formats = args.output_formats()?;
+
+    anyhow::ensure!(
+        !args.writes_to_stdout() || output_formats.len() == 1,
+        "--stdout can only be used with a single --output-format"
+    );
+    anyhow::ensure!(
+        !args.writes_to_stdout() || args.export_langs.len() <= 1,
+        "--stdout can only be used with a single --export-langs language"
+    );
+
+    // Post processing
+    let mut summary = ProcessSummary::default();
+    let mut included = vec![];
+
+    let exclude_categories = exclude_categories
+        .iter()
+        .map(|name| normalize_title(name))
+        .collect::<Vec<_>>();
+    let exclude_categories = exclude_categories.as_slice();
+    let allowlist = args.category_allowlist()?;
+    let allowed = |title: &String| allowlist.as_ref().is_none_or(|names| names.contains(title));
+
+    for article in ko_articles {
+        let category_title = normalize_title(&article.category_titles[&LangEnum::KR]);
+
+        if ignored_ids.contains(&article.id)
+            || exclude_categories.contains(&category_title)
+            || !allowed(&category_title)
+            || args
+                .article_filter_expr
+                .as_ref()
+                .is_some_and(|filter| !filter.matches(article, &args.lang, args.contents_format))
+        {
+            summary.excluded += 1;
+            continue;
+        }
+
+        let has_contents = match args.multilang_inline {
+            true => args
+                .multilang_order
+                .iter()
+                .any(|lang| article.contents.contains_key(lang)),
+            false => article.contents.contains_key(&args.lang),
+        };
+
+        if !has_contents {
+            summary.skipped_missing_lang += 1;
+            continue;
+        }
+
+        // Blank contents do not count, an aligned corpus needs actual text on every side
+        let complete = args.require_languages.iter().all(|lang| {
+            article
+                .contents
+                .get(lang)
+                .is_some_and(|contents| !contents.trim().is_empty())
+        });
+
+        if !complete {
+            summary.skipped_incomplete += 1;
+            continue;
+        }
+
+        if let Some(min_languages) = args.content_min_languages {
+            let languages = article
+                .contents
+                .values()
+                .filter(|contents| !contents.trim().is_empty())
+                .count();
+
+            if languages < min_languages {
+                summary.skipped_few_languages += 1;
+                continue;
+            }
+        }
+
+        included.push(article);
+        summary.articles_written += 1;
+    }
+
+    let mut category_names = category_names
+        .iter()
+        .filter(|name| !exclude_categories.contains(name))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if matches!(
+        args.category_sort,
+        CategorySort::Position | CategorySort::Id
+    ) {
+        let mut ranks = HashMap::new();
+        collect_category_ranks(categories, args.category_sort, &mut ranks);
+
+        category_names.sort_by_key(|name| match ranks.get(name.as_str()) {
+            Some(rank) => (0, *rank),
+            None => (1, 0),
+        });
+    }
+
+    let category_map = args.category_map()?;
+    for name in category_names.iter_mut() {
+        if let Some(display) = category_map.get(name.as_str()) {
+            *name = display.clone();
+        }
+    }
+
+    // By the names as written, renamed ones included
+    if args.category_sort == CategorySort::Title {
+        category_names.sort();
+    }
+
+    if args.dedupe_category_names {
+        let mut seen = HashSet::new();
+        category_names.retain(|name| seen.insert(name.clone()));
+    }
+
+    let mut category_tree_lines = vec![];
+    render_category_tree(
+        args,
+        categories,
+        0,
+        exclude_categories,
+        &mut category_tree_lines,
+    );
+
+    let mut category_rows = vec![];
+    flatten_category_rows(args, categories, &mut category_rows);
+
+    let category_names_body = category_names.join("\n");
+    let category_tree_body = category_tree_lines.join("\n");
+
+    summary.write_export(
+        args,
+        final_dir.join(&args.categories_file),
+        category_names_body.as_bytes(),
+        category_names.is_empty(),
+    )?;
+    summary.write_export(
+        args,
+        final_dir.join("category_tree.md"),
+        category_tree_body.as_bytes(),
+        category_tree_lines.is_empty(),
+    )?;
+
+    let mut categories_csv = csv::Writer::from_writer(vec![]);
+    for row in &category_rows {
+        categories_csv.serialize(row)?;
+    }
+    summary.write_export(
+        args,
+        final_dir.join("categories.csv"),
+        &categories_csv.into_inner()?,
+        category_rows.is_empty(),
+    )?;
+
+    if args.categories_only {
+        return Ok(summary);
+    }
+
+    let articles_index = included
+        .iter()
+        .map(|article| index_entry(article))
+        .collect::<Vec<_>>();
+    summary.write_export(
+        args,
+        final_dir.join("articles_index.json"),
+        &serde_json::to_vec_pretty(&articles_index)?,
+        articles_index.is_empty(),
+    )?;
+
+    let templates = match &args.template_dir {
+        Some(dir) => Some(Arc::new(Templates::load(
+            dir,
+            categories,
+            args.title_lang().clone(),
+        )?)),
+        None => None,
+    };
+
+    if args.article_pages && !args.writes_to_stdout() {
+        let pages_dir = final_dir.join("articles");
+        std::fs::create_dir_all(&pages_dir).unwrap();
+
+        for article in &included {
+            let canonical_url = args.canonical_url(article.id);
+
+            let page = match &templates {
+                Some(templates) if templates.has(template::ARTICLE_PAGE) => templates.render(
+                    template::ARTICLE_PAGE,
+                    article,
+                    args.lang.clone(),
+                    args.title_lang().clone(),
+                    canonical_url.as_deref(),
+                )?,
+                _ => export::article_page(
+                    article,
+                    args.lang.clone(),
+                    args.title_lang().clone(),
+                    args.snippet_length,
+                    args.contents_format,
+                    canonical_url.as_deref(),
+                ),
+            };
+
+            let page_path = match args.group_output_by_category {
+                true => {
+                    let path = pages_dir.join(grouped_page_path(args, article));
+                    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                    path
+                }
+                false => pages_dir.join(format!("{}.html", article.id)),
+            };
+
+            summary.write_file(args, page_path, page.as_bytes())?;
+        }
+    }
+
+    let items = if args.hierarchical {
+        hierarchical_items(args, categories, &category_map, &included)
+    } else {
+        included
+            .iter()
+            .map(|article| ExportItem::Article(article))
+            .collect()
+    };
+
+    // Without --export-langs there is a single, unsuffixed file per format in --lang
+    let langs = match args.export_langs.is_empty() {
+        true => vec![None],
+        false => args.export_langs.iter().cloned().map(Some).collect(),
+    };
+    let writers = output_formats
+        .iter()
+        .flat_map(|&format| langs.iter().map(move |lang| (format, lang.clone())))
+        .collect::<Vec<_>>();
+
+    // The writers share nothing but the read-only article set, so each format and language
+    // renders and writes its own file on a separate thread
+    let single_format = output_formats.len() == 1;
+    let results = std::thread::scope(|scope| {
+        let handles = writers
+            .iter()
+            .map(|(format, lang)| {
+                let items = &items;
+                let templates = templates.clone();
+
+                scope.spawn(move || {
+                    write_articles(
+                        args,
+                        *format,
+                        lang.clone(),
+                        items,
+                        templates,
+                        final_dir,
+                        single_format,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("article writer panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    let mut errors = vec![];
+    for ((format, lang), result) in writers.iter().zip(results) {
+        match (result, lang) {
+            (Ok(writer_summary), _) => summary.merge(writer_summary),
+            (Err(e), Some(lang)) => {
+                errors.push(format!("{} ({:?}): {:#}", format.extension(), lang, e))
+            }
+            (Err(e), None) => errors.push(format!("{}: {:#}", format.extension(), e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        anyhow::bail!("Failed to write articles\n{}", errors.join("\n"));
+    }
+
+    Ok(summary)
+}
+
+// Lets `--content-delimiter '\n---\n'` be typed without a literal newline
+fn unescape_delimiter(delimiter: &str) -> String {
+    delimiter.replace("\\n", "\n").replace("\\t", "\t")
+}
+
+// Articles in category tree order, each category that still has articles opening with a heading
+fn hierarchical_items<'a>(
+    args: &Arguments,
+    categories: &'a [CategoryChildResponse],
+    category_map: &'a HashMap<String, String>,
+    included: &[&'a ArticleDataResponse],
+) -> Vec<ExportItem<'a>> {
+    let mut remaining = included
+        .iter()
+        .map(|article| (article.id, *article))
+        .collect::<HashMap<_, _>>();
+
+    let mut items = vec![];
+    push_tree_items(
+        args,
+        categories,
+        category_map,
+        0,
+        &mut remaining,
+        &mut items,
+    );
+
+    // Articles the cached tree does not know about (e.g. no categories.json) go last
+    let mut leftovers = included
+        .iter()
+        .filter(|article| remaining.contains_key(&article.id))
+        .map(|article| ExportItem::Article(article))
+        .collect();
+    items.append(&mut leftovers);
+
+    items
+}
+
+fn push_tree_items<'a>(
+    args: &Arguments,
+    children: &'a [CategoryChildResponse],
+    category_map: &'a HashMap<String, String>,
+    depth: usize,
+    remaining: &mut HashMap<i32, &'a ArticleDataResponse>,
+    items: &mut Vec<ExportItem<'a>>,
+) {
+    for child in children {
+        if child.type_ == "ARTICLE" {
+            if let Some(article) = remaining.remove(&child.id) {
+                items.push(ExportItem::Article(article));
+            }
+        } else if child.type_ == "CATEGORY" {
+            let mut category_items = vec![];
+            push_tree_items(
+                args,
+                &child.children,
+                category_map,
+                depth + 1,
+                remaining,
+                &mut category_items,
+            );
+
+            if !category_items.is_empty() {
+                let title = localized(&child.titles, args.title_lang());
+
+                items.push(ExportItem::Heading {
+                    depth,
+                    title: category_map.get(title).map_or(title, String::as_str),
+                });
+                items.append(&mut category_items);
+            }
+        }
+    }
+}
+
+// `export_lang` is one of --export-langs, whose file is named after it
+fn write_articles(
+    args: &Arguments,
+    format: OutputFormat,
+    export_lang: Option<LangEnum>,
+    items: &[ExportItem],
+    templates: Option<Arc<Templates>>,
+    final_dir: &Path,
+    single_format: bool,
+) -> anyhow::Result<ProcessSummary> {
+    let mut summary = ProcessSummary::default();
+    let content_delimiter = args
+        .content_only
+        .then(|| unescape_delimiter(&args.content_delimiter));
+    let lang = export_lang.clone().unwrap_or_else(|| args.lang.clone());
+    let mut writer = ArticleWriter::new(format, lang.clone())
+        .with_title_lang(args.category_title_lang.clone().unwrap_or(lang))
+        .with_fields(args.selected_fields()?)
+        .with_content_only(content_delimiter)
+        .with_prefixed_ids(args.prefix_article_ids)
+        .with_inline_langs(args.inline_langs())
+        .with_code_fences(!args.strip_code_fences)
+        .with_templates(templates)
+        .with_merged_attachments(args.merge_attachments_into_content)
+        .with_combined_subtitles(args.combine_subtitles)
+        .with_contents_format(args.contents_format)
+        .with_flattened_newlines(args.flatten_contents_newlines)
+        .with_content_wrapper(
+            unescape_delimiter(args.content_prefix.as_deref().unwrap_or_default()),
+            unescape_delimiter(args.content_suffix.as_deref().unwrap_or_default()),
+        )
+        .with_lang_columns(match &args.lang_column_order {
+            Some(langs) if langs.is_empty() => export::present_langs(items),
+            Some(langs) => langs.clone(),
+            None => vec![],
+        })
+        .with_url_template(args.article_url_template.clone());
+
+    for item in items {
+        writer.push_item(item)?;
+    }
+
+    let body = writer.finish();
+
+    if args.writes_to_stdout() {
+        let body = args.newline.apply(body.as_bytes());
+        let body = args
+            .output_encoding
+            .encode(&body, format == OutputFormat::Json)?;
+        std::io::stdout().lock().write_all(&body)?;
+        summary.bytes_written += body.len() as u64;
+    } else {
+        let mut file_path = match &args.output_file {
+            Some(output_file) => writer.output_path(output_file, single_format),
+            None => final_dir.join(writer.output_path(&args.articles_file, single_format)),
+        };
+        if let Some(lang) = export_lang {
+            let code = lang.code().to_lowercase();
+            let extension = match file_path.extension() {
+                Some(extension) => format!("{}.{}", code, extension.to_string_lossy()),
+                None => code,
+            };
+            file_path.set_extension(extension);
+        }
+        let empty = !items
+            .iter()
+            .any(|item| matches!(item, ExportItem::Article(_)));
+
+        summary.write_export(args, file_path, body.as_bytes(), empty)?;
+    }
+
+    Ok(summary)
+}
+
+#[derive(Debug, Default)]
+pub struct ProcessSummary {
+    pub articles_written: usize,
+    pub excluded: usize,
+    pub skipped_missing_lang: usize,
+    pub skipped_incomplete: usize,
+    pub skipped_few_languages: usize,
+    pub bytes_written: u64,
+    pub files: Vec<PathBuf>,
+    pub files_skipped: Vec<PathBuf>,
+}
+
+impl ProcessSummary {
+    fn write_file(&mut self, args: &Arguments, path: PathBuf, body: &[u8]) -> anyhow::Result<()> {
+        if path.exists() {
+            match args.overwrite_policy {
+                OverwritePolicy::Skip => {
+                    self.files_skipped.push(path);
+                    return Ok(());
+                }
+                OverwritePolicy::Overwrite => {}
+                OverwritePolicy::Backup => {
+                    std::fs::rename(&path, backup_path(&path))?;
+                }
+            }
+        }
+
+        let body = args.newline.apply(body);
+        let json = path
+            .extension()
+            .is_some_and(|extension| extension == "json");
+        let body = args.output_encoding.encode(&body, json)?;
+
+        write_atomic(&path, &body)?;
+
+        self.bytes_written += body.len() as u64;
+        self.files.push(path);
+
+        Ok(())
+    }
+
+    // An empty export looks like a successful run, so unless asked for it is left out instead,
+    // along with the copy an earlier run staged
+    fn write_export(
+        &mut self,
+        args: &Arguments,
+        path: PathBuf,
+        body: &[u8],
+        empty: bool,
+    ) -> anyhow::Result<()> {
+        if !empty || args.write_empty_files {
+            return self.write_file(args, path, body);
+        }
+
+        eprintln!(
+            "Nothing to write to {}, skipping it (pass --write-empty-files to write it anyway)",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        );
+        // An --output-file elsewhere is the user's own and stays untouched
+        if path.starts_with(&args.output_dir) && path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+
+        Ok(())
+    }
+
+    // Reports the paths where the files end up rather than where they were staged
+    fn relocate(&mut self, from: &Path, to: &Path) {
+        for path in self.files.iter_mut().chain(self.files_skipped.iter_mut()) {
+            if let Ok(relative) = path.strip_prefix(from) {
+                *path = to.join(relative);
+            }
+        }
+    }
+
+    fn merge(&mut self, other: ProcessSummary) {
+        self.bytes_written += other.bytes_written;
+        self.files.extend(other.files);
+        self.files_skipped.extend(other.files_skipped);
+    }
+
+    fn print(&self, locale: Option<Locale>) {
+        for file in &self.files {
+            println!("Wrote {}", file.display());
+        }
+
+        for file in &self.files_skipped {
+            println!("Skipped existing {}", file.display());
+        }
+
+        println!(
+            "{} articles written, {} excluded, {} skipped for missing language, {} bytes",
+            format_count(locale, self.articles_written),
+            format_count(locale, self.excluded),
+            format_count(locale, self.skipped_missing_lang),
+            format_count(locale, self.bytes_written)
+        );
+
+        if self.skipped_incomplete > 0 {
+            println!(
+                "{} articles skipped for lacking a --require-languages language",
+                format_count(locale, self.skipped_incomplete)
+            );
+        }
+
+        if self.skipped_few_languages > 0 {
+            println!(
+                "{} articles skipped for having fewer languages than --content-min-languages",
+                format_count(locale, self.skipped_few_languages)
+            );
+        }
+    }
+}
+
+// all_articles.md -> all_articles.20240131-235959.md
+fn backup_path(path: &Path) -> PathBuf {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+
+    // Two backups within the same second get a counter, so neither replaces the other
+    (0..)
+        .map(|counter| {
+            let suffix = match counter {
+                0 => timestamp.to_string(),
+                counter => format!("{}-{}", timestamp, counter),
+            };
+
+            let file_name = match path.extension() {
+                Some(extension) => format!("{}.{}.{}", stem, suffix, extension.to_string_lossy()),
+                None => format!("{}.{}", stem, suffix),
+            };
+
+            path.with_file_name(file_name)
+        })
+        .find(|backup| !backup.exists())
+        .unwrap()
+}
+
+fn render_category_tree(
+    args: &Arguments,
+    children: &[CategoryChildResponse],
+    depth: usize,
+    exclude_categories: &[String],
+    lines: &mut Vec<String>,
+) {
+    for child in children {
+        if child.type_ != "CATEGORY" || exclude_categories.contains(&child.titles[&LangEnum::KR]) {
+            continue;
+        }
+
+        let mut node = child;
+        let mut heading = localized(&child.titles, args.title_lang()).to_string();
+
+        if args.flatten_single_child {
+            while let [only_child] = node.children.as_slice() {
+                if only_child.type_ != "CATEGORY" {
+                    break;
+                }
+
+                heading = format!(
+                    "{} / {}",
+                    heading,
+                    localized(&only_child.titles, args.title_lang())
+                );
+                node = only_child;
+            }
+        }
+
+        if args.category_depth_labels {
+            // Markdown has no heading below ######, deeper categories share it
+            lines.push(format!("{} {}\n", "#".repeat((depth + 1).min(6)), heading));
+        } else {
+            lines.push(format!("{}- {}", "  ".repeat(depth), heading));
+        }
+
+        render_category_tree(args, &node.children, depth + 1, exclude_categories, lines);
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ArticleIndexEntry<'a> {
+    id: i32,
+    category_id: i32,
+    status: &'a str,
+    languages: Vec<LangEnum>,
+    has_image: bool,
+    // contents_hash of the exported contents, as stored in content_hashes.json
+    content_hash: String,
+}
+
+fn index_entry(article: &ArticleDataResponse) -> ArticleIndexEntry<'_> {
+    let mut languages = article
+        .contents
+        .keys()
+        .chain(article.titles.keys())
+        .cloned()
+        .collect::<Vec<_>>();
+    languages.sort_by_key(LangEnum::order);
+    languages.dedup();
+
+    ArticleIndexEntry {
+        id: article.id,
+        category_id: article.category_id,
+        status: &article.status,
+        languages,
+        has_image: article
+            .image_url
+            .as_deref()
+            .is_some_and(|url| !url.is_empty()),
+        content_hash: contents_hash(article),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CategoryRow<'a> {
+    id: i32,
+    parent_id: Option<i32>,
+    position: i32,
+    #[serde(rename = "type")]
+    type_: &'a str,
+    status: &'a str,
+    title: &'a str,
+}
+
+fn flatten_category_rows<'a>(
+    args: &Arguments,
+    children: &'a [CategoryChildResponse],
+    rows: &mut Vec<CategoryRow<'a>>,
+) {
+    for child in children {
+        rows.push(CategoryRow {
+            id: child.id,
+            parent_id: child.parent_id,
+            position: child.position,
+            type_: &child.type_,
+            status: &child.status,
+            title: localized(&child.titles, args.title_lang()),
+        });
+
+        flatten_category_rows(args, &child.children, rows);
+    }
+}
+
+// Falls back to the Korean title, which the API always provides
+fn localized<'a>(texts: &'a HashMap<LangEnum, String>, lang: &LangEnum) -> &'a str {
+    texts
+        .get(lang)
+        .or_else(|| texts.get(&LangEnum::KR))
+        .map_or("", |text| text.as_str())
+}
+
+pub async fn run(mut args: Arguments) -> anyhow::Result<()> {
+    // Once, so every write of the run lands in the same directory even across midnight
+    args.output_dir = expand_output_dir(&args.output_dir, chrono::Local::now())?;
+
+    match args.command {
+        Some(Command::Browse) => {
+            let data = read_from_local(&args).await?;
+            return browse::run(&args, &data);
+        }
+        Some(Command::Coverage {
+            identical_as_untranslated,
+        }) => {
+            let data = read_from_local(&args).await?;
+            let identical = match identical_as_untranslated {
+                true => Some(coverage::identical_contents(&data)),
+                false => None,
+            };
+            let rows = coverage::collect(&args, &data, identical.as_deref());
+            coverage::print(&args, &data, &rows, identical.as_deref())?;
+
+            return Ok(());
+        }
+        Some(Command::DownloadAttachments) => {
+            let data = read_from_local(&args).await?;
+            let crawler = Crawler::new(&args, HashSet::new())?;
+            let manifest = download_attachments(&crawler, &data.ko_articles).await?;
+            crawler.retry_stats.print(args.locale);
+
+            println!(
+                "{} of {} attachments downloaded into {}",
+                format_count(
+                    args.locale,
+                    manifest.iter().filter(|entry| entry.file.is_some()).count()
+                ),
+                format_count(args.locale, manifest.len()),
+                args.output_dir.join("attachments").display()
+            );
+
+            return Ok(());
+        }
+        Some(Command::Doctor) => return doctor::run(&args).await,
+        Some(Command::GenFixtures { count, seed }) => {
+            return fixtures::generate(&args, count, seed)
+        }
+        Some(Command::Prune { dry_run }) => return prune::run(&args, dry_run).await,
+        Some(Command::Schema) => {
+            let schema_dir = args.output_dir.join("schema");
+            std::fs::create_dir_all(&schema_dir).unwrap();
+
+            let schemas = [
+                ("article", schemars::schema_for!(ArticleDataResponse)),
+                ("category", schemars::schema_for!(CategoryResponse)),
+            ];
+
+            for (name, schema) in schemas {
+                let file_path = schema_dir.join(format!("{}.schema.json", name));
+                write_atomic(&file_path, &serde_json::to_vec_pretty(&schema)?)?;
+
+                println!("Wrote {}", file_path.display());
+            }
+
+            return Ok(());
+        }
+        Some(Command::Serve { port }) => {
+            let data = read_from_local(&args).await?;
+            return serve::run(&args, data, port).await;
+        }
+        Some(Command::Wordfreq { top, ref stopwords }) => {
+            let data = read_from_local(&args).await?;
+            let file_path = wordfreq::run(&args, &data, top, stopwords.as_deref())?;
+
+            println!("Wrote {}", file_path.display());
+
+            return Ok(());
+        }
+        None => {}
+    }
+
+    confirm_output_dir(&args)?;
+
+    if args.watch {
+        return watch::run(args).await;
+    }
+
+    let Some(sources_path) = &args.sources else {
+        return crawl_site(args).await;
+    };
+
+    let sources: Vec<Source> = serde_json::from_str(&std::fs::read_to_string(sources_path)?)?;
+    let mut errors = vec![];
+
+    for source in sources {
+        if !args.writes_to_stdout() {
+            println!("Crawling {}", source.name);
+        }
+
+        let mut site_args = args.clone();
+        site_args.output_dir = args.output_dir.join(&source.name);
+        site_args.categories_url = source.categories_url;
+        site_args.article_base_url = source.article_base_url;
+
+        // A site whose categories fail to load is reported with the others instead of
+        // ending the crawl of the remaining sites
+        match crawl_site(site_args).await {
+            Ok(()) => {}
+            Err(e) if !args.keeps_going() => return Err(e),
+            Err(e) => errors.push(format!("{}: {:#}", source.name, e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        for error in &errors {
+            eprintln!("Failed to crawl {}", error);
+        }
+
+        anyhow::bail!("{} sites failed to crawl", errors.len());
+    }
+
+    Ok(())
+}
+
+// Keeps `amount` random articles in their original order, so the tree exports stay in shape
+fn sample_articles(articles: &mut Vec<ArticleDataResponse>, amount: usize, seed: Option<u64>) {
+    if amount >= articles.len() {
+        return;
+    }
+
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut picked = rand::seq::index::sample(&mut rng, articles.len(), amount).into_vec();
+    picked.sort_unstable();
+
+    let mut picked = picked.into_iter().peekable();
+    let mut index = 0;
+    articles.retain(|_| {
+        let keep = picked.next_if_eq(&index).is_some();
+        index += 1;
+        keep
+    });
+}
+
+async fn crawl_site(args: Arguments) -> anyhow::Result<()> {
+    // Surface a bad --output-file extension or field name before spending time on the crawl
+    args.output_formats()?;
+    args.selected_fields()?;
+    args.category_map()?;
+    args.category_allowlist()?;
+    let category_denylist = args.category_denylist()?;
+    if !args.reads_offline() {
+        args.check_site_urls()?;
+    }
+    anyhow::ensure!(
+        !(args.writes_to_stdout() && args.summary_json.as_deref() == Some(Path::new("-"))),
+        "--summary-json - cannot share stdout with the export"
+    );
+
+    let started = Instant::now();
+    let ignored_ids = load_ignored_ids(Path::new(".dfuignore"))?;
+    let crawler = Crawler::new(&args, ignored_ids.clone())?;
+
+    let mut data = if let Some(InputSource::Json(path)) = &args.source {
+        read_from_json(path)?
+    } else if let Some(InputSource::Ndjson(path)) = &args.source {
+        read_from_ndjson(path)?
+    } else if args.use_local {
+        read_from_local(&args).await?
+    } else if args.retry_only_failed {
+        retry_failed(&crawler).await?
+    } else if let Some(range) = args.article_id_range.clone() {
+        read_id_range(&crawler, range).await?
+    } else {
+        read_from_web(&crawler).await?
+    };
+
+    // Nothing in them to export, and every language lookup would come up empty
+    data.ko_articles.retain(|article| {
+        if article.contents.is_empty() {
+            eprintln!("Skipping article {}, it has no contents at all", article.id);
+        }
+
+        !article.contents.is_empty()
+    });
+
+    if !args.exclude_status.is_empty() {
+        exclude_statuses(&args, &mut data);
+    }
+
+    // Before any filter, so an article left out of this export keeps its stored hash
+    if !args.reads_offline() && !args.no_cache_write {
+        track_content_changes(&args, &data.ko_articles)?;
+    }
+
+    if let Some(amount) = args.sample {
+        sample_articles(
+            &mut data.ko_articles,
+            amount,
+            args.seed.or(args.shuffle_seed),
+        );
+    }
+
+    if args.title_case {
+        title_case_english(&mut data);
+    }
+
+    if let Some(command) = &args.content_transform {
+        transform_contents(command, args.no_color, &mut data.ko_articles);
+    }
+
+    if let Some(seed) = args.shuffle_seed {
+        data.ko_articles.shuffle(&mut StdRng::seed_from_u64(seed));
+    }
+
+    if args.categories_from_articles {
+        data.category_names = category_names_from_articles(&data.ko_articles);
+        report_category_discrepancies(&args, &data)?;
+    }
+
+    let exclude_categories = ["명예의 전당", "스페셜", "아트던展"]
+        .iter()
+        .map(|s| s.to_string())
+        .chain(category_denylist)
+        .collect::<Vec<_>>();
+
+    let summary = post_process(&args, &data, &exclude_categories, &ignored_ids).await?;
+
+    if !args.writes_to_stdout() && !args.compact_summary {
+        summary.print(args.locale);
+    }
+
+    if let Some(path) = &args.merge_into {
+        merge_into(&args, path, &data.ko_articles)?;
+    }
+
+    let attachment_manifest = if args.download_attachments {
+        download_attachments(&crawler, &data.ko_articles).await?
+    } else {
+        vec![]
+    };
+
+    if !args.writes_to_stdout()
+        && !args.compact_summary
+        && (!args.reads_offline() || args.download_attachments)
+    {
+        crawler.retry_stats.print(args.locale);
+    }
+
+    let article_fetches = FetchTimings::from_durations(&crawler.fetch_times.lock().unwrap());
+
+    if !args.writes_to_stdout() && !args.compact_summary {
+        if let Some(timings) = &article_fetches {
+            timings.print(args.locale);
+        }
+    }
+
+    let stats = CrawlStats {
+        retries: &crawler.retry_stats,
+        article_fetches,
+        finished_at: chrono::Local::now().to_rfc3339(),
+    };
+
+    if !args.reads_offline() {
+        write_atomic(
+            &args.output_dir.join("final").join("crawl_stats.json"),
+            &serde_json::to_vec_pretty(&stats)?,
+        )?;
+    }
+
+    if args.missing_images_report {
+        write_missing_images_report(
+            &args.output_dir,
+            args.newline,
+            &data.ko_articles,
+            &attachment_manifest,
+        )?;
+    }
+
+    if args.validate_links {
+        validate_links(&crawler, &data.ko_articles).await?;
+    }
+
+    if args.compact_summary {
+        let line = format!(
+            "fetched={} failed={} excluded={} bytes={} seconds={:.1}",
+            data.ko_articles.len(),
+            data.errors.len(),
+            summary.excluded,
+            summary.bytes_written,
+            started.elapsed().as_secs_f64()
+        );
+
+        // Keeps piped exports clean
+        if args.writes_to_stdout() {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    if let Some(locale) = args.locale {
+        if !args.writes_to_stdout() && !args.compact_summary {
+            println!("Finished at {}", locale.timestamp(chrono::Local::now()));
+        }
+    }
+
+    if let Some(path) = &args.summary_json {
+        let run_summary = RunSummary {
+            fetched: data.ko_articles.len(),
+            written: summary.articles_written,
+            excluded: summary.excluded,
+            skipped_missing_lang: summary.skipped_missing_lang,
+            skipped_incomplete: summary.skipped_incomplete,
+            skipped_few_languages: summary.skipped_few_languages,
+            bytes_written: summary.bytes_written,
+            failed: &data.errors,
+            elapsed_secs: started.elapsed().as_secs_f64(),
+            stats: &stats,
+        };
+        let body = serde_json::to_vec_pretty(&run_summary)?;
+
+        if path == Path::new("-") {
+            println!("{}", String::from_utf8(body)?);
+        } else {
+            if let Some(parent) = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+            {
+                std::fs::create_dir_all(parent).unwrap();
+            }
+            write_atomic(path, &body)?;
+        }
+    }
+
+    if !data.errors.is_empty() {
+        for error in &data.errors {
+            eprintln!("Failed to crawl {}", error);
+        }
+
+        anyhow::bail!("{} articles failed to download", data.errors.len());
+    }
+
+    Ok(())
+}
+
+fn expand_output_dir(
+    output_dir: &Path,
+    now: chrono::DateTime<chrono::Local>,
+) -> anyhow::Result<PathBuf> {
+    let template = output_dir.to_string_lossy();
+    if !template.contains('{') {
+        return Ok(output_dir.to_path_buf());
+    }
+
+    let mut expanded = String::new();
+    let mut rest = template.as_ref();
+
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| anyhow::anyhow!("Unclosed placeholder in --output-dir {}", template))?;
+
+        let format = match &rest[start + 1..end] {
+            "date" => "%Y-%m-%d",
+            "time" => "%H-%M-%S",
+            "datetime" => "%Y-%m-%d_%H-%M-%S",
+            format if format.starts_with('%') => format,
+            other => anyhow::bail!("Unknown placeholder {{{}}} in --output-dir", other),
+        };
+
+        let items = chrono::format::StrftimeItems::new(format).collect::<Vec<_>>();
+        anyhow::ensure!(
+            !items.contains(&chrono::format::Item::Error),
+            "Invalid strftime format {{{}}} in --output-dir",
+            format
+        );
+
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&now.format_with_items(items.into_iter()).to_string());
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+
+    Ok(PathBuf::from(expanded))
+}
+
+// A missing directory whose parent is missing too is more likely a typo than a new archive,
+// so creating that whole chain needs --yes or a confirmation
+fn confirm_output_dir(args: &Arguments) -> anyhow::Result<()> {
+    let output_dir = &args.output_dir;
+    let parent_exists = output_dir
+        .parent()
+        .is_none_or(|parent| parent.as_os_str().is_empty() || parent.is_dir());
+
+    if args.yes || args.use_local || output_dir.is_dir() || parent_exists {
+        return Ok(());
+    }
+
+    anyhow::ensure!(
+        std::io::stdin().is_terminal(),
+        "Neither {} nor its parent exist; pass --yes to create them",
+        output_dir.display()
+    );
+
+    eprint!(
+        "Neither {} nor its parent exist. Create them? [y/N] ",
+        output_dir.display()
+    );
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+
+    anyhow::ensure!(
+        matches!(answer.trim(), "y" | "Y" | "yes"),
+        "Not creating {}",
+        output_dir.display()
+    );
+
+    Ok(())
+}
+
+// One category title per line, normalized like the crawled titles; `#` only starts a comment
+// at the beginning of a line, since titles may contain one
+fn load_category_list(path: &Path) -> anyhow::Result<HashSet<String>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", path.display(), e))?;
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(normalize_title)
+        .collect())
+}
+
+// One article id per line, `#` starts a comment
+fn load_ignored_ids(path: &Path) -> anyhow::Result<HashSet<i32>> {
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    let mut ids = HashSet::new();
+
+    for (line_number, line) in content.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let id = line.parse().map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid article id {:?} in {} at line {}",
+                line,
+                path.display(),
+                line_number + 1
+            )
+        })?;
+
+        ids.insert(id);
+    }
+
+    Ok(ids)
+}
+
+async fn visit_article(
+    crawler: &Crawler,
+    child: &CategoryChildResponse,
+    state: &mut CrawlState,
+) -> anyhow::Result<()> {
+    // Replaying a breadth-first pass: whatever it fetched is only put back in tree order
+    if let Some(prefetched) = &mut state.prefetched {
+        if let Some(article) = prefetched.remove(&child.id) {
+            state.ko_articles.push(article);
+        }
+        return Ok(());
+    }
+
+    if crawler.args.categories_only {
+        // Only the taxonomy was asked for
+    } else if crawler.ignored_ids.contains(&child.id) {
+        if !crawler.args.writes_to_stdout() {
+            println!("Skipping ignored article {}", child.id);
+        }
+    } else if state.seen_articles.contains(&child.id) {
+        // Linked under several categories, the first link already fetched it
+        if !crawler.args.writes_to_stdout() {
+            println!("Skipping duplicate link to article {}", child.id);
+        }
+    } else if !title_matches(&child.titles, crawler.args.title_contains.as_deref()) {
+        // Filtered out by --title-contains, which saves the request entirely
+    } else {
+        state.seen_articles.insert(child.id);
+
+        // Articles up to the checkpoint were already fetched, reuse their cached copy
+        let cached = match state.resume_after {
+            Some(last_id) => {
+                if child.id == last_id {
+                    state.resume_after = None;
+                }
+
+                load_cached_article(&crawler.args.output_dir, child.id).ok()
+            }
+            None if state.completed.contains(&child.id) => {
+                load_cached_article(&crawler.args.output_dir, child.id).ok()
+            }
+            None => None,
+        };
+
+        let mut article = match cached {
+            Some(article) => article,
+            None => match get_article_content(crawler, child.id).await {
+                Ok(article) => article,
+                Err(e) if crawler.args.graceful_404 && is_not_found(&e) => {
+                    eprintln!("Skipping article {}, listed but not found", child.id);
+                    state.missing_articles.push(child.id);
+                    return Ok(());
+                }
+                Err(e) if e.is::<DisallowedByRobots>() => {
+                    eprintln!("Skipping article {}, disallowed by robots.txt", child.id);
+                    return Ok(());
+                }
+                Err(e) if !crawler.args.keeps_going() => return Err(e),
+                Err(e) => {
+                    state.errors.push(format!("article {}: {:#}", child.id, e));
+                    state.failed_ids.push(child.id);
+                    return Ok(());
+                }
+            },
+        };
+
+        // Without the cached copies there would be nothing to resume from
+        if !crawler.args.no_cache_write {
+            write_checkpoint(&crawler.args.output_dir, child.id)?;
+            if state.completed.insert(child.id) {
+                append_completed(&crawler.args.output_dir, child.id)?;
+            }
+        }
+        normalize_article_titles(&mut article.data);
+
+        if crawler.args.prints_progress(state.ko_articles.len() + 1) {
+            println!(
+                "{} - {}",
+                article.data.category_titles[&LangEnum::KR],
+                article.data.titles[&LangEnum::KR]
+            );
+        }
+
+        state.ko_articles.push(article.data);
+    }
+
+    Ok(())
+}
+
+// One root at a time, so the per-category counts fall out of the traversal itself
+async fn iterate_roots(
+    crawler: &Crawler,
+    roots: &[CategoryChildResponse],
+    state: &mut CrawlState,
+    root_counts: &mut Vec<(String, usize)>,
+) -> anyhow::Result<()> {
+    for root in roots {
+        let articles_before = state.ko_articles.len();
+
+        iterate_children(crawler, std::slice::from_ref(root), 0, state).await?;
+
+        root_counts.push((
+            root.titles[&LangEnum::KR].clone(),
+            state.ko_articles.len() - articles_before,
+        ));
+    }
+
+    Ok(())
+}
+
+// Fetches level by level with an explicit queue; category names are left to the replay
+async fn iterate_breadth_first(
+    crawler: &Crawler,
+    roots: &[CategoryChildResponse],
+    state: &mut CrawlState,
+) -> anyhow::Result<()> {
+    let mut queue = roots.iter().map(|root| (root, 0)).collect::<VecDeque<_>>();
+
+    while let Some((child, depth)) = queue.pop_front() {
+        if crawler.args.excludes_status(&child.status) {
+            continue;
+        }
+
+        if child.type_ == "ARTICLE" {
+            visit_article(crawler, child, state).await?;
+        }
+
+        let within_depth = crawler
+            .args
+            .max_depth
+            .is_none_or(|max_depth| depth < max_depth);
+
+        if within_depth {
+            queue.extend(child.children.iter().map(|child| (child, depth + 1)));
+        }
+    }
+
+    Ok(())
+}
+
+#[async_recursion]
+async fn iterate_children(
+    crawler: &Crawler,
+    children: &[CategoryChildResponse],
+    depth: usize,
+    state: &mut CrawlState,
+) -> anyhow::Result<()> {
+    for child in children {
+        if crawler.args.excludes_status(&child.status) {
+            continue;
+        }
+
+        let child_type = &child.type_;
+        let articles_before = state.ko_articles.len();
+        let name_index = state.category_names.len();
+
+        if child_type == "ARTICLE" {
+            visit_article(crawler, child, state).await?;
+        } else if child_type == "CATEGORY" {
+            if restore_finished(crawler, child.id, state) {
+                continue;
+            }
+
+            state
+                .category_names
+                .push(normalize_title(&child.titles[&LangEnum::KR]));
+        }
+
+        let within_depth = crawler
+            .args
+            .max_depth
+            .is_none_or(|max_depth| depth < max_depth);
+
+        if !child.children.is_empty() && within_depth {
+            iterate_children(crawler, &child.children, depth + 1, state).await?;
+        }
+
+        // Nested empty categories already removed themselves, so only this entry is left to check
+        if child_type == "CATEGORY"
+            && !crawler.args.include_empty_categories
+            && !crawler.args.categories_only
+            && state.ko_articles.len() == articles_before
+        {
+            state.category_names.remove(name_index);
+        }
+
+        if child_type == "CATEGORY" {
+            record_finished(crawler, child.id, name_index, articles_before, state)?;
+        }
+    }
+
+    Ok(())
+}
+
+// API titles carry stray (often non-breaking) spaces, which break exact-match exclusion
+fn normalize_title(title: &str) -> String {
+    title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .nfc()
+        .collect()
+}
+
+fn normalize_titles(titles: &mut HashMap<LangEnum, String>) {
+    for title in titles.values_mut() {
+        *title = normalize_title(title);
+    }
+}
+
+fn normalize_article_titles(article: &mut ArticleDataResponse) {
+    normalize_titles(&mut article.titles);
+    normalize_titles(&mut article.category_titles);
+}
+
+const SMALL_WORDS: [&str; 17] = [
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "nor", "of", "on", "or", "the",
+    "to", "vs",
+];
+
+// Small words stay lowercase except first, last and after a colon. Words with capitals past
+// their first letter (DFU, McKay) are kept, unless the whole title is shouted in capitals
+fn title_case(title: &str) -> String {
+    let shouted = !title.chars().any(char::is_lowercase);
+    let words = title.split(' ').collect::<Vec<_>>();
+    let mut starts_phrase = true;
+
+    let cased = words
+        .iter()
+        .enumerate()
+        .map(|(index, word)| {
+            let lower = word.to_lowercase();
+            let bare = lower.trim_matches(|c: char| !c.is_alphanumeric());
+            let is_last = index + 1 == words.len();
+
+            let cased = if !starts_phrase && !is_last && SMALL_WORDS.contains(&bare) {
+                lower.clone()
+            } else if !shouted && word.chars().skip(1).any(char::is_uppercase) {
+                word.to_string()
+            } else {
+                capitalize(&lower)
+            };
+
+            starts_phrase = word.ends_with(':');
+            cased
+        })
+        .collect::<Vec<_>>();
+
+    cased.join(" ")
+}
+
+// Uppercases the first letter, after any leading punctuation such as a quote
+fn capitalize(word: &str) -> String {
+    match word.char_indices().find(|(_, c)| c.is_alphabetic()) {
+        Some((start, c)) => format!(
+            "{}{}{}",
+            &word[..start],
+            c.to_uppercase(),
+            &word[start + c.len_utf8()..]
+        ),
+        None => word.to_string(),
+    }
+}
+
+// Existing articles keep their place in the list, new ones follow in crawl order
+fn merge_into(
+    args: &Arguments,
+    path: &Path,
+    articles: &[ArticleDataResponse],
+) -> anyhow::Result<()> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => anyhow::bail!("Cannot open {}: {}", path.display(), e),
+    };
+
+    let mut merged: Vec<serde_json::Value> = match content.trim().is_empty() {
+        true => vec![],
+        false => probe::parse::<Vec<ArticleDataResponse>>(&content)
+            .map_err(|e| anyhow::anyhow!("{} is not a list of articles: {}", path.display(), e))?
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<_, _>>()?,
+    };
+
+    let mut positions = merged
+        .iter()
+        .enumerate()
+        .filter_map(|(index, article)| Some((article.get("id")?.as_i64()?, index)))
+        .collect::<HashMap<_, _>>();
+
+    let (mut added, mut updated, mut unchanged) = (0, 0, 0);
+    for article in articles {
+        let value = serde_json::to_value(article)?;
+
+        match positions.get(&(article.id as i64)) {
+            Some(&index) if merged[index] == value => unchanged += 1,
+            Some(&index) => {
+                merged[index] = value;
+                updated += 1;
+            }
+            None => {
+                positions.insert(article.id as i64, merged.len());
+                merged.push(value);
+                added += 1;
+            }
+        }
+    }
+
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    write_atomic(path, &serde_json::to_vec_pretty(&merged)?)?;
+
+    if !args.writes_to_stdout() {
+        println!(
+            "Merged into {}: {} added, {} updated, {} unchanged, {} articles in total",
+            path.display(),
+            format_count(args.locale, added),
+            format_count(args.locale, updated),
+            format_count(args.locale, unchanged),
+            format_count(args.locale, merged.len())
+        );
+    }
+
+    Ok(())
+}
+
+// Counted per status: the categories and articles excluded for their own status, not the
+// articles that only went away with their category
+fn exclude_statuses(args: &Arguments, data: &mut CrawlData) {
+    fn prune(
+        args: &Arguments,
+        children: &mut Vec<CategoryChildResponse>,
+        counts: &mut std::collections::BTreeMap<String, usize>,
+        names: &mut HashSet<String>,
+    ) {
+        children.retain(|child| {
+            if !args.excludes_status(&child.status) {
+                return true;
+            }
+
+            *counts.entry(child.status.to_uppercase()).or_default() += 1;
+            if child.type_ == "CATEGORY" {
+                names.insert(normalize_title(localized(&child.titles, &LangEnum::KR)));
+            }
+            false
+        });
+
+        for child in children {
+            prune(args, &mut child.children, counts, names);
+        }
+    }
+
+    let mut counts = std::collections::BTreeMap::new();
+    let mut excluded_names = HashSet::new();
+
+    let mut listed_before = HashSet::new();
+    collect_article_ids(&data.categories, &mut listed_before);
+    prune(args, &mut data.categories, &mut counts, &mut excluded_names);
+    let mut listed_after = HashSet::new();
+    collect_article_ids(&data.categories, &mut listed_after);
+
+    data.ko_articles.retain(|article| {
+        if listed_before.contains(&article.id) && !listed_after.contains(&article.id) {
+            return false;
+        }
+
+        if args.excludes_status(&article.status) {
+            *counts.entry(article.status.to_uppercase()).or_default() += 1;
+            return false;
+        }
+
+        true
+    });
+    data.category_names
+        .retain(|name| !excluded_names.contains(name));
+
+    if !counts.is_empty() && !args.writes_to_stdout() {
+        let counts = counts
+            .iter()
+            .map(|(status, count)| format!("{} {}", status, format_count(args.locale, *count)))
+            .collect::<Vec<_>>();
+        println!("Excluded by --exclude-status: {}", counts.join(", "));
+    }
+}
+
+fn title_case_english(data: &mut CrawlData) {
+    fn apply(titles: &mut HashMap<LangEnum, String>) {
+        if let Some(title) = titles.get_mut(&LangEnum::EN) {
+            *title = title_case(title);
+        }
+    }
+
+    fn apply_tree(children: &mut [CategoryChildResponse]) {
+        for child in children {
+            apply(&mut child.titles);
+            apply_tree(&mut child.children);
+        }
+    }
+
+    for article in &mut data.ko_articles {
+        apply(&mut article.titles);
+        apply(&mut article.category_titles);
+    }
+    apply_tree(&mut data.categories);
+}
+
+// A failing command only costs its own article the transform, with a warning; with --no-color
+// the command is handed NO_COLOR, as it would have been from the shell
+fn transform_contents(command: &str, no_color: bool, articles: &mut [ArticleDataResponse]) {
+    articles.par_iter_mut().for_each(|article| {
+        let id = article.id;
+
+        for (lang, contents) in &mut article.contents {
+            match run_transform(command, no_color, id, lang, contents) {
+                Ok(transformed) => *contents = transformed,
+                Err(e) => eprintln!(
+                    "--content-transform failed for article {} ({:?}), keeping its contents: {:#}",
+                    id, lang, e
+                ),
+            }
+        }
+    });
+}
+
+fn run_transform(
+    command: &str,
+    no_color: bool,
+    id: i32,
+    lang: &LangEnum,
+    contents: &str,
+) -> anyhow::Result<String> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = std::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = std::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+
+    if no_color {
+        shell.env("NO_COLOR", "1");
+    }
+
+    let mut child = shell
+        .arg(command)
+        .env("DFU_ARTICLE_ID", id.to_string())
+        .env("DFU_LANG", lang.code())
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()?;
+
+    // Written from another thread, a command that answers before reading everything would
+    // otherwise fill its stdout pipe while we are still blocked on its stdin
+    let mut stdin = child.stdin.take().unwrap();
+    let input = contents.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let output = child.wait_with_output()?;
+    // A command that ignores its input closes the pipe early, which is not an error of its own
+    writer.join().unwrap().ok();
+
+    anyhow::ensure!(output.status.success(), "exited with {}", output.status);
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+fn normalize_category_titles(children: &mut [CategoryChildResponse]) {
+    for child in children {
+        normalize_titles(&mut child.titles);
+        normalize_category_titles(&mut child.children);
+    }
+}
+
+// Siblings in `position` order; equal positions fall back to the id so the order never depends
+// on how the API happened to list them
+fn sort_by_position(
+    args: &Arguments,
+    children: &mut [CategoryChildResponse],
+    parent_id: Option<i32>,
+) {
+    children.sort_by_key(|child| (child.position, child.id));
+
+    if args.warn_duplicate_positions {
+        for pair in children.windows(2) {
+            if pair[0].position == pair[1].position {
+                eprintln!(
+                    "Duplicate position {} under {}: {} and {}",
+                    pair[0].position,
+                    parent_id.map_or("the root".to_string(), |id| format!("category {}", id)),
+                    pair[0].id,
+                    pair[1].id
+                );
+            }
+        }
+    }
+
+    for child in children {
+        sort_by_position(args, &mut child.children, Some(child.id));
+    }
+}
+
+fn title_matches(titles: &HashMap<LangEnum, String>, keyword: Option<&str>) -> bool {
+    let Some(keyword) = keyword else {
+        return true;
+    };
+
+    let keyword = keyword.to_lowercase();
+
+    titles
+        .values()
+        .any(|title| title.to_lowercase().contains(&keyword))
+}
+
+fn checkpoint_path(output_dir: &Path) -> PathBuf {
+    output_dir.join("checkpoint.json")
+}
+
+// checkpoint.json -> checkpoint.categories.jsonl, so --resume-from finds it from the checkpoint
+fn finished_categories_path(checkpoint_path: &Path) -> PathBuf {
+    checkpoint_path.with_extension("categories.jsonl")
+}
+
+// A category finishing later also holds the lines of its subcategories, the lookup by id on
+// the way down only ever reaches the outermost one
+fn load_finished_categories(path: &Path) -> anyhow::Result<HashMap<i32, FinishedCategory>> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Ok(HashMap::new());
+    };
+
+    // As with completed.jsonl, a last line without its newline may have been cut short
+    content
+        .split_inclusive('\n')
+        .filter(|line| line.ends_with('\n') && !line.trim().is_empty())
+        .map(|line| {
+            let finished: FinishedCategory = serde_json::from_str(line)?;
+            Ok((finished.id, finished))
+        })
+        .collect()
+}
+
+// Appended rather than rewritten, the log only ever grows by the category just finished
+fn record_finished(
+    crawler: &Crawler,
+    id: i32,
+    name_index: usize,
+    articles_before: usize,
+    state: &CrawlState,
+) -> anyhow::Result<()> {
+    // The breadth-first pass and its replay do not finish categories one at a time
+    if crawler.args.no_cache_write || crawler.args.fetch_order != FetchOrder::Depth {
+        return Ok(());
+    }
+
+    let finished = FinishedCategory {
+        id,
+        category_names: state.category_names[name_index..].to_vec(),
+        article_ids: state.ko_articles[articles_before..]
+            .iter()
+            .map(|article| article.id)
+            .collect(),
+    };
+
+    let path = finished_categories_path(&checkpoint_path(&crawler.args.output_dir));
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(&finished)?)?;
+
+    Ok(())
+}
+
+// Brings back a category the interrupted run finished from its cached articles; false when
+// there is none, or a cached copy went missing and the category has to be crawled again
+fn restore_finished(crawler: &Crawler, id: i32, state: &mut CrawlState) -> bool {
+    let Some(finished) = state.finished.get(&id) else {
+        return false;
+    };
+
+    let articles = finished
+        .article_ids
+        .iter()
+        .map(|id| load_cached_article(&crawler.args.output_dir, *id))
+        .collect::<anyhow::Result<Vec<_>>>();
+    let Ok(articles) = articles else {
+        return false;
+    };
+
+    state
+        .category_names
+        .extend(finished.category_names.iter().cloned());
+
+    for mut article in articles {
+        if state.resume_after == Some(article.data.id) {
+            state.resume_after = None;
+        }
+
+        normalize_article_titles(&mut article.data);
+        state.seen_articles.insert(article.data.id);
+        state.completed.insert(article.data.id);
+        state.ko_articles.push(article.data);
+    }
+
+    true
+}
+
+fn write_checkpoint(output_dir: &Path, last_article_id: i32) -> anyhow::Result<()> {
+    let file_path = checkpoint_path(output_dir);
+
+    std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+
+    write_atomic(
+        &file_path,
+        &serde_json::to_vec(&Checkpoint { last_article_id })?,
+    )?;
+
+    Ok(())
+}
+
+fn completed_path(output_dir: &Path) -> PathBuf {
+    output_dir.join("completed.json")
+}
+
+// Ids finished since completed.json was last written, one per line; an interrupted crawl
+// leaves it behind for the next one to pick up
+fn completed_log_path(output_dir: &Path) -> PathBuf {
+    completed_path(output_dir).with_extension("jsonl")
+}
+
+fn load_completed(output_dir: &Path) -> anyhow::Result<HashSet<i32>> {
+    let file_path = completed_path(output_dir);
+
+    let mut completed = HashSet::new();
+    if file_path.exists() {
+        let ids: Vec<i32> = serde_json::from_reader(File::open(file_path)?)?;
+        completed.extend(ids);
+    }
+
+    if let Ok(log) = std::fs::read_to_string(completed_log_path(output_dir)) {
+        // A last line without its newline may be a cut short id, so it is not counted
+        completed.extend(
+            log.split_inclusive('\n')
+                .filter(|line| line.ends_with('\n'))
+                .filter_map(|line| line.trim().parse::<i32>().ok()),
+        );
+    }
+
+    Ok(completed)
+}
+
+// One line per article rather than rewriting the whole list, which grew with every article
+fn append_completed(output_dir: &Path, id: i32) -> anyhow::Result<()> {
+    std::fs::create_dir_all(output_dir).unwrap();
+    let mut log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(completed_log_path(output_dir))?;
+    writeln!(log, "{}", id)?;
+
+    Ok(())
+}
+
+// Folds the log into completed.json once the traversal is over
+fn write_completed(output_dir: &Path, completed: &HashSet<i32>) -> anyhow::Result<()> {
+    let mut ids = completed.iter().copied().collect::<Vec<_>>();
+    ids.sort_unstable();
+
+    std::fs::create_dir_all(output_dir).unwrap();
+    write_atomic(&completed_path(output_dir), &serde_json::to_vec(&ids)?)?;
+
+    let log_path = completed_log_path(output_dir);
+    if log_path.exists() {
+        std::fs::remove_file(log_path)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct ContentChange {
+    article_id: i32,
+    change: &'static str,
+}
+
+// sha256 of every language's contents; independent of the API's `modified` flag, which says
+// nothing about when or whether the text itself changed
+fn contents_hash(article: &ArticleDataResponse) -> String {
+    let mut contents = article
+        .contents
+        .iter()
+        .map(|(lang, text)| (lang.code(), text))
+        .collect::<Vec<_>>();
+    contents.sort();
+
+    let mut hashed = vec![];
+    for (lang, text) in contents {
+        hashed.extend_from_slice(lang.as_bytes());
+        hashed.push(0);
+        hashed.extend_from_slice(text.as_bytes());
+        hashed.push(0);
+    }
+
+    sha256_hex(&hashed)
+}
+
+fn load_content_hashes(output_dir: &Path) -> anyhow::Result<Option<HashMap<i32, String>>> {
+    let hashes_path = output_dir.join("content_hashes.json");
+    match hashes_path.exists() {
+        true => Ok(Some(serde_json::from_reader(File::open(&hashes_path)?)?)),
+        false => Ok(None),
+    }
+}
+
+// Holds the crawled contents against <output-dir>/content_hashes.json from the previous crawl and
+// lists new and changed articles in final/content_changes.csv (or .json)
+fn track_content_changes(
+    args: &Arguments,
+    ko_articles: &[ArticleDataResponse],
+) -> anyhow::Result<()> {
+    let hashes_path = args.output_dir.join("content_hashes.json");
+    let previous = load_content_hashes(&args.output_dir)?;
+
+    let mut hashes = previous.clone().unwrap_or_default();
+    let mut changes = vec![];
+    for article in ko_articles {
+        let hash = contents_hash(article);
+
+        let change = match previous.as_ref().map(|previous| previous.get(&article.id)) {
+            Some(None) => Some("new"),
+            Some(Some(stored)) if *stored != hash => Some("changed"),
+            _ => None,
+        };
+        if let Some(change) = change {
+            changes.push(ContentChange {
+                article_id: article.id,
+                change,
+            });
+        }
+
+        hashes.insert(article.id, hash);
+    }
+
+    // A BTreeMap keeps the file stable between runs
+    let sorted = hashes
+        .into_iter()
+        .collect::<std::collections::BTreeMap<_, _>>();
+    std::fs::create_dir_all(&args.output_dir).unwrap();
+    write_atomic(&hashes_path, &serde_json::to_vec_pretty(&sorted)?)?;
+
+    // The first crawl has nothing to compare with
+    if previous.is_none() {
+        return Ok(());
+    }
+
+    changes.sort_by_key(|change| change.article_id);
+    let file_path = report::write(
+        args,
+        &args.output_dir.join("final"),
+        "content_changes",
+        &changes,
+    )?;
+
+    if !args.writes_to_stdout() {
+        println!(
+            "{} articles changed and {} new since the last crawl, listed in {}",
+            changes
+                .iter()
+                .filter(|change| change.change == "changed")
+                .count(),
+            changes
+                .iter()
+                .filter(|change| change.change == "new")
+                .count(),
+            file_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+fn failed_path(output_dir: &Path) -> PathBuf {
+    output_dir.join("failed.json")
+}
+
+// Replaced after every crawl, so it only ever names what the latest run could not fetch
+fn write_failed(crawler: &Crawler, failed_ids: &[i32]) -> anyhow::Result<()> {
+    if crawler.args.no_cache_write {
+        return Ok(());
+    }
+
+    let mut ids = failed_ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+
+    std::fs::create_dir_all(&crawler.args.output_dir).unwrap();
+    write_atomic(
+        &failed_path(&crawler.args.output_dir),
+        &serde_json::to_vec(&ids)?,
+    )?;
+
+    Ok(())
+}
+
+// Fetches only the articles the previous crawl failed on, then exports them together with
+// everything it did cache
+async fn retry_failed(crawler: &Crawler) -> anyhow::Result<CrawlData> {
+    let file_path = failed_path(&crawler.args.output_dir);
+    anyhow::ensure!(
+        file_path.exists(),
+        "No failure list at {}; --retry-only-failed needs a previous crawl",
+        file_path.display()
+    );
+
+    let failed_ids: Vec<i32> = serde_json::from_reader(File::open(&file_path)?)?;
+    if !crawler.args.writes_to_stdout() {
+        println!("Retrying {} failed articles", failed_ids.len());
+    }
+
+    let mut errors = vec![];
+    let mut still_failing = vec![];
+    for id in failed_ids {
+        match get_article_content(crawler, id).await {
+            Ok(_) => {}
+            Err(e) if !crawler.args.keeps_going() => return Err(e),
+            Err(e) => {
+                errors.push(format!("article {}: {:#}", id, e));
+                still_failing.push(id);
+            }
+        }
+    }
+
+    write_failed(crawler, &still_failing)?;
+
+    let mut data = read_from_local(&crawler.args).await?;
+    data.errors = errors;
+
+    Ok(data)
+}
+
+fn load_cached_article(output_dir: &Path, id: i32) -> anyhow::Result<ArticleResponse> {
+    let file_path = output_dir.join("articles").join(format!("{}.json", id));
+
+    let article: ArticleResponse = probe::parse(&std::fs::read_to_string(file_path)?)?;
+
+    Ok(article)
+}
+
+async fn get_category_response(crawler: &Crawler, url: &str) -> anyhow::Result<CategoryResponse> {
+    let file_path = crawler
+        .args
+        .output_dir
+        .join("category")
+        .join("categories.json");
+
+    let body = get_page_to_file(crawler, url, &file_path, None, |_| false).await?;
+
+    let mut category_response: CategoryResponse =
+        probe::parse_with(&body, crawler.args.parse_strict)?;
+    // A live site always has categories; an empty list is a failed request answered with 200
+    anyhow::ensure!(
+        !category_response.data.is_empty(),
+        "The category listing at {} is empty, the request most likely failed",
+        url
+    );
+    normalize_category_titles(&mut category_response.data);
+    sort_by_position(&crawler.args, &mut category_response.data, None);
+
+    Ok(category_response)
+}
+
+// The listing is kept for the crawl itself; the sample article is fetched and parsed but not
+// written to articles/, so the crawl proper handles it like any other (--cache-http and
+// --archive-raw still keep their copies of the response, as for every request)
+async fn head_check(crawler: &Crawler) -> anyhow::Result<CategoryResponse> {
+    let categories_url = &crawler.args.categories_url;
+    let categories = get_category_response(crawler, categories_url)
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Preflight failed, the category listing at {} did not load: {:#}",
+                categories_url,
+                e
+            )
+        })?;
+
+    let filters = SampleFilters {
+        args: &crawler.args,
+        ignored_ids: &crawler.ignored_ids,
+        allowlist: crawler.args.category_allowlist()?,
+        denylist: crawler.args.category_denylist()?,
+    };
+    let roots = select_roots(&crawler.args, &categories.data)?;
+
+    let sample_id = sample_article_id(&filters, roots, 0, None);
+    let Some(sample_id) = sample_id.filter(|_| !crawler.args.categories_only) else {
+        if !crawler.args.writes_to_stdout() {
+            println!("Preflight passed: {} loads", categories_url);
+        }
+        return Ok(categories);
+    };
+
+    let url = article_url(&crawler.args.article_base_url, sample_id);
+    let page = get_page_content(crawler, &url, crawler.args.max_article_bytes, None)
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Preflight failed, sample article {} at {} did not load: {:#}",
+                sample_id,
+                url,
+                e
+            )
+        })?;
+
+    let body = String::from_utf8_lossy(&page.bytes);
+    probe::parse_with::<ArticleResponse>(&body, crawler.args.parse_strict).map_err(|e| {
+        anyhow::anyhow!(
+            "Preflight failed, sample article {} at {} is not an article response: {:#}",
+            sample_id,
+            url,
+            e
+        )
+    })?;
+
+    if !crawler.args.writes_to_stdout() {
+        println!(
+            "Preflight passed: {} and sample article {} load",
+            categories_url, sample_id
+        );
+    }
+
+    Ok(categories)
+}
+
+// What decides whether an article is crawled and exported, short of fetching it
+struct SampleFilters<'a> {
+    args: &'a Arguments,
+    ignored_ids: &'a HashSet<i32>,
+    allowlist: Option<HashSet<String>>,
+    denylist: HashSet<String>,
+}
+
+// The first article in tree order that the crawl fetches and the export keeps, so a preflight
+// never vouches for one that is skipped anyway; `category` is the title of the closest category,
+// `depth` counts from the top-level categories like iterate_children does
+fn sample_article_id(
+    filters: &SampleFilters,
+    children: &[CategoryChildResponse],
+    depth: usize,
+    category: Option<&str>,
+) -> Option<i32> {
+    children.iter().find_map(|child| {
+        if filters.args.excludes_status(&child.status) {
+            return None;
+        }
+
+        if child.type_ == "ARTICLE" {
+            let category = normalize_title(category.unwrap_or_default());
+            let wanted = !filters.ignored_ids.contains(&child.id)
+                && title_matches(&child.titles, filters.args.title_contains.as_deref())
+                && !filters.denylist.contains(&category)
+                && filters
+                    .allowlist
+                    .as_ref()
+                    .is_none_or(|names| names.contains(&category));
+
+            if wanted {
+                return Some(child.id);
+            }
+        }
+
+        let within_depth = filters
+            .args
+            .max_depth
+            .is_none_or(|max_depth| depth < max_depth);
+        if !within_depth {
+            return None;
+        }
+
+        let category = match child.type_.as_str() {
+            "CATEGORY" => Some(localized(&child.titles, &LangEnum::KR)),
+            _ => category,
+        };
+        sample_article_id(filters, &child.children, depth + 1, category)
+    })
+}
+
+// Fetches a page and caches it at file_path. With --verify-downloads a written file whose
+// size disagrees with the Content-Length header is treated as truncated and fetched again.
+// A cached copy is revalidated with its ETag/Last-Modified and reused on 304 Not Modified,
+// and left alone when `unchanged` says the new body holds nothing new.
+async fn get_page_to_file(
+    crawler: &Crawler,
+    url: &str,
+    file_path: &Path,
+    max_bytes: Option<u64>,
+    unchanged: impl Fn(&[u8]) -> bool,
+) -> anyhow::Result<String> {
+    let validators_path = validators_path(file_path);
+    let cached_validators = match file_path.exists() && validators_path.exists() {
+        true => serde_json::from_slice::<Validators>(&std::fs::read(&validators_path)?).ok(),
+        false => None,
+    };
+
+    let mut attempt = 0;
+
+    loop {
+        let page = get_page_content(crawler, url, max_bytes, cached_validators.as_ref()).await?;
+
+        if page.not_modified {
+            return Ok(std::fs::read_to_string(file_path)?);
+        }
+
+        // The validators belong to the new body, so they stay with the old file as well
+        let keep_cached = file_path.exists() && unchanged(&page.bytes);
+
+        if !crawler.args.no_cache_write && !keep_cached {
+            write_downloaded(crawler, file_path, &page.bytes).await?;
+
+            if page.validators.etag.is_some() || page.validators.last_modified.is_some() {
+                write_downloaded(
+                    crawler,
+                    &validators_path,
+                    &serde_json::to_vec(&page.validators)?,
+                )
+                .await?;
+            }
+        }
+
+        if let (true, Some(expected)) = (crawler.args.verify_downloads, page.content_length) {
+            let written = if crawler.args.no_cache_write || keep_cached {
+                page.bytes.len() as u64
+            } else {
+                std::fs::metadata(file_path)?.len()
+            };
+
+            if written != expected {
+                anyhow::ensure!(
+                    attempt < crawler.args.retries,
+                    "{} is {} bytes but Content-Length was {}",
+                    file_path.display(),
+                    written,
+                    expected
+                );
+
+                eprintln!(
+                    "{} is {} bytes but Content-Length was {}, downloading again",
+                    file_path.display(),
+                    written,
+                    expected
+                );
+
+                attempt += 1;
+                continue;
+            }
+        }
+
+        return Ok(String::from_utf8(page.bytes)?);
+    }
+}
+
+// Writes next to the target and renames it into place, so an interrupted crawl never
+// leaves a truncated file behind
+fn write_atomic(file_path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+    let mut temp_name = file_path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = file_path.with_file_name(temp_name);
+
+    let mut file = File::create(&temp_path)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+
+    std::fs::rename(temp_path, file_path)?;
+
+    Ok(())
+}
+
+// Off the async workers and behind --max-concurrent-writes, ready for when downloads run
+// concurrently; until then there is never more than one write waiting on the permits
+async fn write_downloaded(crawler: &Crawler, file_path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+    let _permit = crawler.write_permits.acquire().await?;
+    let file_path = file_path.to_path_buf();
+    let bytes = bytes.to_vec();
+
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        write_atomic(&file_path, &bytes)
+    })
+    .await?
+}
+
+struct FetchedPage {
+    bytes: Vec<u8>,
+    content_length: Option<u64>,
+    content_type: Option<String>,
+    validators: Validators,
+    not_modified: bool,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+// `101.json` keeps its validators in `101.json.http`, which read_from_local never mistakes for an article
+fn validators_path(file_path: &Path) -> PathBuf {
+    let mut file_name = file_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".http");
+
+    file_path.with_file_name(file_name)
+}
+
+async fn get_page_content(
+    crawler: &Crawler,
+    url: &str,
+    max_bytes: Option<u64>,
+    validators: Option<&Validators>,
+) -> anyhow::Result<FetchedPage> {
+    if crawler.args.cache_http {
+        if let Some(bytes) = read_http_cache(&crawler.args, url, max_bytes) {
+            return Ok(FetchedPage {
+                content_length: Some(bytes.len() as u64),
+                content_type: None,
+                bytes,
+                validators: Validators::default(),
+                not_modified: false,
+            });
+        }
+    }
+
+    if crawler.args.respect_robots && !robots_allowed(crawler, url).await? {
+        return Err(DisallowedByRobots(url.to_string()).into());
+    }
+
+    let page = with_retries(crawler, url, || {
+        fetch_page(crawler, url, max_bytes, validators)
+    })
+    .await?;
+
+    if crawler.args.archive_raw && !page.not_modified {
+        archive_raw_response(crawler, url, &page.bytes).await?;
+    }
+
+    if crawler.args.cache_http && !page.not_modified {
+        let file_path = http_cache_path(&crawler.args.output_dir, url);
+        write_downloaded(crawler, &file_path, &page.bytes).await?;
+    }
+
+    Ok(page)
+}
+
+// Sends `request` again with backoff while it fails for a transient reason (no answer, a 5xx
+// or a 429), up to --retries and within --retry-deadline-secs
+async fn with_retries<T, F>(
+    crawler: &Crawler,
+    url: &str,
+    mut request: impl FnMut() -> F,
+) -> anyhow::Result<T>
+where
+    F: std::future::Future<Output = anyhow::Result<T>>,
+{
+    let max_backoff = Duration::from_secs(crawler.args.max_backoff_secs);
+    let deadline = Duration::from_secs(crawler.args.retry_deadline_secs);
+    let started = Instant::now();
+
+    let stats = &crawler.retry_stats;
+
+    let mut attempt = 0;
+    loop {
+        match request().await {
+            Ok(response) => {
+                if attempt > 0 {
+                    stats.succeeded_after_retry.fetch_add(1, Ordering::Relaxed);
+                }
+
+                return Ok(response);
+            }
+            Err(e)
+                if attempt < crawler.args.retries
+                    && e.downcast_ref::<reqwest::Error>().is_some_and(is_retryable) =>
+            {
+                let delay = backoff_delay(attempt, max_backoff);
+
+                if started.elapsed() + delay > deadline {
+                    stats.failed_permanently.fetch_add(1, Ordering::Relaxed);
+                    return Err(e.context(format!(
+                        "Retry deadline of {:?} exceeded for {}",
+                        deadline, url
+                    )));
+                }
+
+                eprintln!("Request to {} failed ({}), retrying in {:?}", url, e, delay);
+
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                stats.retries.fetch_add(1, Ordering::Relaxed);
+            }
+            // A request that was never retried (e.g. a 404) did not fail for lack of retries
+            Err(e) => {
+                if attempt > 0 {
+                    stats.failed_permanently.fetch_add(1, Ordering::Relaxed);
+                }
+                return Err(e);
+            }
+        }
+    }
+}
+
+// Every GET goes through here: categories, articles, attachments and robots.txt alike. Only
+// check_link sends its own HEAD, behind the same --rps limit and host permits
+async fn fetch_page(
+    crawler: &Crawler,
+    url: &str,
+    max_bytes: Option<u64>,
+    validators: Option<&Validators>,
+) -> anyhow::Result<FetchedPage> {
+    crawler.rate_limiter.until_ready().await;
+    let _permit = acquire_host_permit(crawler, url).await?;
+
+    let mut request = crawler.client.get(url);
+    if let Some(validators) = validators {
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let mut res = request.send().await?.error_for_status()?;
+
+    // Once per host: the pool reuses connections, so the first answer speaks for the rest
+    if crawler.args.verbose {
+        let host = res.url().host_str().unwrap_or_default().to_string();
+        if crawler.logged_versions.lock().unwrap().insert(host.clone()) {
+            eprintln!("Connected to {} over {:?}", host, res.version());
+        }
+    }
+
+    let header = |name| {
+        res.headers()
+            .get(name)
+            .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+            .map(|value| value.to_string())
+    };
+    let validators = Validators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+    let content_type = header(CONTENT_TYPE);
+
+    if res.status() == StatusCode::NOT_MODIFIED {
+        return Ok(FetchedPage {
+            bytes: vec![],
+            content_length: None,
+            content_type: None,
+            validators,
+            not_modified: true,
+        });
+    }
+
+    // Compressed bodies are decoded transparently, and then report no Content-Length at all
+    let content_length = res.content_length();
+
+    // Streamed chunk by chunk so an oversized body is dropped before it is buffered whole
+    let limit = max_bytes.unwrap_or(u64::MAX);
+    let too_large = || anyhow::anyhow!("Response of {} is larger than {} bytes", url, limit);
+
+    if content_length.is_some_and(|length| length > limit) {
+        return Err(too_large());
+    }
+
+    let mut bytes = vec![];
+    while let Some(chunk) = res.chunk().await? {
+        bytes.extend_from_slice(&chunk);
+
+        if bytes.len() as u64 > limit {
+            return Err(too_large());
+        }
+    }
+
+    Ok(FetchedPage {
+        bytes,
+        content_length,
+        content_type,
+        validators,
+        not_modified: false,
+    })
+}
+
+#[derive(Debug)]
+struct DisallowedByRobots(String);
+
+impl std::fmt::Display for DisallowedByRobots {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} is disallowed by robots.txt", self.0)
+    }
+}
+
+impl std::error::Error for DisallowedByRobots {}
+
+const ROBOTS_USER_AGENT: &str = "dfu_crawler";
+
+async fn robots_allowed(crawler: &Crawler, url: &str) -> anyhow::Result<bool> {
+    let parsed = reqwest::Url::parse(url)?;
+    let origin = parsed.origin().ascii_serialization();
+
+    // Held across the fetch, so concurrent first requests to a host wait for a single robots.txt
+    let mut robots = crawler.robots.lock().await;
+    if !robots.contains_key(&origin) {
+        let robots_url = format!("{}/robots.txt", origin);
+
+        // As RFC 9309 has it: a missing robots.txt allows everything, an unreachable one nothing
+        let body = match fetch_page(crawler, &robots_url, None, None).await {
+            Ok(page) => String::from_utf8_lossy(&page.bytes).into_owned(),
+            Err(e)
+                if e.downcast_ref::<reqwest::Error>()
+                    .and_then(|e| e.status())
+                    .is_some_and(|status| status.is_client_error()) =>
+            {
+                String::new()
+            }
+            Err(e) => {
+                eprintln!(
+                    "Could not fetch {} ({:#}), treating it as Disallow: /",
+                    robots_url, e
+                );
+                "User-agent: *\nDisallow: /\n".to_string()
+            }
+        };
+
+        robots.insert(origin.clone(), body);
+    }
+
+    Ok(DefaultMatcher::default().one_agent_allowed_by_robots(
+        &robots[&origin],
+        ROBOTS_USER_AGENT,
+        url,
+    ))
+}
+
+fn is_retryable(error: &reqwest::Error) -> bool {
+    match error.status() {
+        Some(status) => status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+        None => true,
+    }
+}
+
+// Exponential backoff with ±50% jitter so concurrent retries don't line up
+fn backoff_delay(attempt: u32, max_backoff: Duration) -> Duration {
+    let base = Duration::from_secs(1 << attempt.min(16));
+    let jitter = rand::thread_rng().gen_range(0.5..1.5);
+
+    base.mul_f64(jitter).min(max_backoff)
+}
+
+async fn archive_raw_response(crawler: &Crawler, url: &str, bytes: &[u8]) -> anyhow::Result<()> {
+    let file_name = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    let file_path = crawler.args.output_dir.join("raw").join(file_name);
+
+    write_downloaded(crawler, &file_path, bytes).await
+}
+
+fn http_cache_path(output_dir: &Path, url: &str) -> PathBuf {
+    output_dir.join("http_cache").join(url_hash(url))
+}
+
+// FNV-1a, which unlike DefaultHasher gives the same name in every build and so across runs
+fn url_hash(url: &str) -> String {
+    let hash = url.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{:016x}", hash)
+}
+
+// Misses on entries older than --cache-ttl, and on ones over the current --max-article-bytes
+fn read_http_cache(args: &Arguments, url: &str, max_bytes: Option<u64>) -> Option<Vec<u8>> {
+    let file_path = http_cache_path(&args.output_dir, url);
+    let metadata = std::fs::metadata(&file_path).ok()?;
+
+    if let Some(ttl) = args.cache_ttl {
+        let age = metadata.modified().ok()?.elapsed().unwrap_or_default();
+
+        if age > Duration::from_secs(ttl) {
+            return None;
+        }
+    }
+
+    if max_bytes.is_some_and(|max_bytes| metadata.len() > max_bytes) {
+        return None;
+    }
+
+    std::fs::read(file_path).ok()
+}
+
+fn check_url(flag: &str, value: &str) -> anyhow::Result<()> {
+    let url = reqwest::Url::parse(value)
+        .map_err(|e| anyhow::anyhow!("invalid URL for {}: {} ({})", flag, value, e))?;
+
+    anyhow::ensure!(
+        matches!(url.scheme(), "http" | "https"),
+        "invalid URL for {}: {}, only http and https are supported",
+        flag,
+        value
+    );
+
+    Ok(())
+}
+
+fn article_url(base_url: &str, id: i32) -> String {
+    if base_url.contains("{}") {
+        base_url.replace("{}", &id.to_string())
+    } else {
+        format!("{}/{}", base_url.trim_end_matches('/'), id)
+    }
+}
+
+/// Fetches and parses a single article, without the retries, rate limiting or
+/// on-disk caching that the crawler applies.
+pub async fn fetch_article(
+    client: &reqwest::Client,
+    id: i32,
+) -> anyhow::Result<ArticleDataResponse> {
+    let res = client
+        .get(article_url(DEFAULT_ARTICLE_BASE_URL, id))
+        .send()
+        .await?
+        .error_for_status()?;
+    let body = res.text().await?;
+
+    let article_response: ArticleResponse = probe::parse(&body)?;
+
+    Ok(article_response.data)
+}
+
+// One semaphore per host, so a slow host only ever holds its own share of the requests that
+// --validate-links has in flight
+async fn acquire_host_permit(
+    crawler: &Crawler,
+    url: &str,
+) -> anyhow::Result<Option<OwnedSemaphorePermit>> {
+    let Some(limit) = crawler.args.concurrency_per_host else {
+        return Ok(None);
+    };
+
+    let host = reqwest::Url::parse(url)?
+        .host_str()
+        .unwrap_or_default()
+        .to_string();
+
+    let semaphore = crawler
+        .host_permits
+        .lock()
+        .unwrap()
+        .entry(host)
+        .or_insert_with(|| Arc::new(Semaphore::new(limit.max(1))))
+        .clone();
+
+    Ok(Some(semaphore.acquire_owned().await?))
+}
+
+// Crawler variant of fetch_article that also caches the response under crawled_data/articles
+async fn get_article_content(crawler: &Crawler, id: i32) -> anyhow::Result<ArticleResponse> {
+    let file_path = crawler
+        .args
+        .output_dir
+        .join("articles")
+        .join(format!("{}.json", id));
+
+    let url = article_url(&crawler.args.article_base_url, id);
+
+    // With --incremental, an article whose contents hash the same as last crawl keeps its cached
+    // copy, so tools watching the cache only see the ones that changed
+    let unchanged = |bytes: &[u8]| {
+        crawler.stored_hashes.get(&id).is_some_and(|stored| {
+            std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|body| probe::parse_with::<ArticleResponse>(body, false).ok())
+                .is_some_and(|response| contents_hash(&response.data) == *stored)
+        })
+    };
+
+    let started = Instant::now();
+    let body = get_page_to_file(
+        crawler,
+        &url,
+        &file_path,
+        crawler.args.max_article_bytes,
+        unchanged,
+    )
+    .await?;
+    crawler.fetch_times.lock().unwrap().push(started.elapsed());
+
+    let article_response: ArticleResponse = probe::parse_with(&body, crawler.args.parse_strict)?;
+
+    Ok(article_response)
+}
+
+#[derive(Debug, Serialize)]
+struct AttachmentManifestEntry {
+    article_id: i32,
+    attachment_id: i32,
+    kind: &'static str,
+    source_url: String,
+    file: Option<PathBuf>,
+    // With --dedupe-attachments-global, the one stored copy `file` links to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shared_file: Option<PathBuf>,
+    sha256: Option<String>,
+    status: String,
+}
+
+// What --verify-attachment-hashes reads back from the previous manifest.json
+#[derive(Deserialize)]
+struct RecordedAttachment {
+    file: Option<PathBuf>,
+    shared_file: Option<PathBuf>,
+    sha256: Option<String>,
+}
+
+#[derive(Clone)]
+struct AttachmentOutcome {
+    file: Option<PathBuf>,
+    sha256: Option<String>,
+    status: String,
+}
+
+async fn download_attachments(
+    crawler: &Crawler,
+    ko_articles: &[ArticleDataResponse],
+) -> anyhow::Result<Vec<AttachmentManifestEntry>> {
+    let attachments_dir = crawler.args.output_dir.join("attachments");
+    let mut seen = HashSet::new();
+    let mut used_paths = HashSet::new();
+    // Source URL -> outcome of its single download
+    let mut shared_downloads = HashMap::<String, AttachmentOutcome>::new();
+    let recorded_hashes = recorded_attachment_hashes(&attachments_dir);
+    // Listed once up front rather than per attachment; the flat layout puts all in one directory
+    let existing_files = match crawler.args.skip_existing_attachments {
+        true => files_by_stem(&attachments_dir),
+        false => HashMap::new(),
+    };
+    let mut manifest = vec![];
+
+    for article in ko_articles {
+        for attachment in article.attachments.values().flatten() {
+            if !seen.insert(attachment.id) {
+                continue;
+            }
+
+            let media = [
+                ("source", attachment.source_url.as_str()),
+                ("thumbnail", attachment.thumbnail_url.as_str()),
+            ];
+
+            for (kind, url) in media {
+                if url.is_empty() || (kind == "thumbnail" && url == attachment.source_url) {
+                    continue;
+                }
+
+                let stem = match kind {
+                    "thumbnail" => format!("{}_thumb", attachment.id),
+                    _ => attachment.id.to_string(),
+                };
+                let (dir, prefix) = if crawler.args.flat_attachments {
+                    (attachments_dir.clone(), format!("{}_", article.id))
+                } else {
+                    (attachments_dir.join(article.id.to_string()), String::new())
+                };
+
+                let url_name = url_file_name(url)
+                    .filter(|_| crawler.args.attachment_name_from == AttachmentNameFrom::Url);
+                let file_path = match url_name {
+                    Some(name) => dir.join(format!("{}{}", prefix, name)),
+                    None => dir.join(media_file_name(&format!("{}{}", prefix, stem), url)),
+                };
+
+                let (outcome, shared_file) = if crawler.args.dedupe_attachments_global {
+                    let shared = match shared_downloads.get(url) {
+                        Some(outcome) => outcome.clone(),
+                        None => {
+                            let shared_path = attachments_dir
+                                .join("shared")
+                                .join(media_file_name(&url_hash(url), url));
+                            let outcome = download_attachment(
+                                crawler,
+                                url,
+                                shared_path,
+                                &stem,
+                                &mut used_paths,
+                                &existing_files,
+                                &recorded_hashes,
+                            )
+                            .await?;
+                            shared_downloads.insert(url.to_string(), outcome.clone());
+
+                            outcome
+                        }
+                    };
+
+                    let file = match &shared.file {
+                        Some(shared_file) => {
+                            // Named after the type the shared copy turned out to be
+                            let file_path = match shared_file.extension() {
+                                Some(extension) => file_path.with_extension(extension),
+                                None => file_path,
+                            };
+                            let file_path = claim_path(&mut used_paths, file_path, &stem);
+                            Some(link_shared(&attachments_dir, &file_path, shared_file)?)
+                        }
+                        None => None,
+                    };
+
+                    let shared_file = shared.file.clone();
+                    (AttachmentOutcome { file, ..shared }, shared_file)
+                } else {
+                    let outcome = download_attachment(
+                        crawler,
+                        url,
+                        file_path,
+                        &stem,
+                        &mut used_paths,
+                        &existing_files,
+                        &recorded_hashes,
+                    )
+                    .await?;
+                    (outcome, None)
+                };
+                let AttachmentOutcome {
+                    file,
+                    sha256,
+                    status,
+                } = outcome;
+
+                if file.is_none() {
+                    eprintln!(
+                        "Failed to download attachment {} of article {}: {}",
+                        url, article.id, status
+                    );
+                }
+
+                manifest.push(AttachmentManifestEntry {
+                    article_id: article.id,
+                    attachment_id: attachment.id,
+                    kind,
+                    source_url: url.to_string(),
+                    file,
+                    shared_file,
+                    sha256,
+                    status,
+                });
+            }
+        }
+    }
+
+    std::fs::create_dir_all(&attachments_dir).unwrap();
+
+    write_atomic(
+        &attachments_dir.join("manifest.json"),
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+
+    Ok(manifest)
+}
+
+// `stem` tells the attachment apart when another one already took the name of file_path
+async fn download_attachment(
+    crawler: &Crawler,
+    url: &str,
+    file_path: PathBuf,
+    stem: &str,
+    used_paths: &mut HashSet<PathBuf>,
+    existing_files: &HashMap<PathBuf, Vec<PathBuf>>,
+    recorded_hashes: &HashMap<PathBuf, String>,
+) -> anyhow::Result<AttachmentOutcome> {
+    if crawler.args.skip_existing_attachments {
+        for path in existing_candidates(existing_files, &file_path, stem) {
+            if used_paths.contains(&path) {
+                continue;
+            }
+
+            if let Some(sha256) = existing_attachment(crawler, &path, recorded_hashes) {
+                used_paths.insert(path.clone());
+                return Ok(AttachmentOutcome {
+                    file: Some(path),
+                    sha256: Some(sha256),
+                    status: "existing".to_string(),
+                });
+            }
+        }
+    }
+
+    let (file, sha256, status) = match get_page_content(crawler, url, None, None).await {
+        Ok(page) => {
+            let file_path = match detected_extension(page.content_type.as_deref(), &page.bytes) {
+                Some(detected) => retyped_path(url, file_path, detected),
+                None => file_path,
+            };
+            let file_path = claim_path(used_paths, file_path, stem);
+            write_downloaded(crawler, &file_path, &page.bytes).await?;
+
+            (
+                Some(file_path),
+                Some(sha256_hex(&page.bytes)),
+                "ok".to_string(),
+            )
+        }
+        Err(e) if is_not_found(&e) => (None, None, "not_found".to_string()),
+        Err(e) => (None, None, format!("error: {}", e)),
+    };
+
+    Ok(AttachmentOutcome {
+        file,
+        sha256,
+        status,
+    })
+}
+
+// Thumbnails often share the basename of their source, and unrelated uploads can too (or come to,
+// once retyped); a name already taken gets the attachment's stem appended to keep them apart
+fn claim_path(used_paths: &mut HashSet<PathBuf>, file_path: PathBuf, stem: &str) -> PathBuf {
+    let file_path = match used_paths.contains(&file_path) {
+        true => with_stem_suffix(&file_path, stem),
+        false => file_path,
+    };
+    used_paths.insert(file_path.clone());
+
+    file_path
+}
+
+fn with_stem_suffix(file_path: &Path, stem: &str) -> PathBuf {
+    let mut name = file_path.file_stem().unwrap_or_default().to_os_string();
+    name.push(format!("_{}", stem));
+    if let Some(extension) = file_path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+
+    file_path.with_file_name(name)
+}
+
+// Every file under the attachments directory, keyed by its path without the extension
+fn files_by_stem(attachments_dir: &Path) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let mut files = HashMap::<PathBuf, Vec<PathBuf>>::new();
+    let mut dirs = vec![attachments_dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => dirs.push(path),
+                Ok(_) => files.entry(path.with_extension("")).or_default().push(path),
+                Err(_) => {}
+            }
+        }
+    }
+
+    files
+}
+
+// Where an earlier run may have saved the attachment: file_path itself, or next to it under
+// either stem claim_path hands out, with the extension of the type it turned out to be
+fn existing_candidates(
+    existing_files: &HashMap<PathBuf, Vec<PathBuf>>,
+    file_path: &Path,
+    stem: &str,
+) -> Vec<PathBuf> {
+    let stems = [
+        file_path.with_extension(""),
+        with_stem_suffix(file_path, stem).with_extension(""),
+    ];
+
+    let mut found = stems
+        .iter()
+        .filter_map(|stem| existing_files.get(stem))
+        .flatten()
+        .filter(|path| *path != file_path)
+        .cloned()
+        .collect::<Vec<_>>();
+    found.sort();
+
+    std::iter::once(file_path.to_path_buf())
+        .chain(found)
+        .collect()
+}
+
+// An empty file is what an interrupted download leaves behind, so only non-empty ones count;
+// with --verify-attachment-hashes the file must also match the hash the last manifest recorded
+fn existing_attachment(
+    crawler: &Crawler,
+    file_path: &Path,
+    recorded_hashes: &HashMap<PathBuf, String>,
+) -> Option<String> {
+    let bytes = std::fs::read(file_path)
+        .ok()
+        .filter(|bytes| !bytes.is_empty())?;
+    let sha256 = sha256_hex(&bytes);
+
+    if crawler.args.verify_attachment_hashes && recorded_hashes.get(file_path) != Some(&sha256) {
+        return None;
+    }
+
+    Some(sha256)
+}
+
+fn recorded_attachment_hashes(attachments_dir: &Path) -> HashMap<PathBuf, String> {
+    let Ok(manifest) = std::fs::read_to_string(attachments_dir.join("manifest.json")) else {
+        return HashMap::new();
+    };
+    let Ok(entries) = serde_json::from_str::<Vec<RecordedAttachment>>(&manifest) else {
+        return HashMap::new();
+    };
+
+    entries
+        .into_iter()
+        .flat_map(|entry| {
+            let sha256 = entry.sha256?;
+            let files = entry.file.into_iter().chain(entry.shared_file);
+
+            Some(files.map(move |file| (file, sha256.clone())))
+        })
+        .flatten()
+        .collect()
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// A relative symlink, so the attachments directory can be moved as a whole
+#[cfg(unix)]
+fn link_shared(
+    attachments_dir: &Path,
+    file_path: &Path,
+    shared_file: &Path,
+) -> anyhow::Result<PathBuf> {
+    let parent = file_path.parent().unwrap();
+    std::fs::create_dir_all(parent).unwrap();
+
+    let depth = parent.strip_prefix(attachments_dir)?.components().count();
+    let mut target = PathBuf::new();
+    for _ in 0..depth {
+        target.push("..");
+    }
+    target.push(shared_file.strip_prefix(attachments_dir)?);
+
+    if file_path.symlink_metadata().is_ok() {
+        std::fs::remove_file(file_path)?;
+    }
+    std::os::unix::fs::symlink(target, file_path)?;
+
+    Ok(file_path.to_path_buf())
+}
+
+// Without symlinks the manifest is the only mapping, every reference points at the shared copy
+#[cfg(not(unix))]
+fn link_shared(
+    _attachments_dir: &Path,
+    _file_path: &Path,
+    shared_file: &Path,
+) -> anyhow::Result<PathBuf> {
+    Ok(shared_file.to_path_buf())
+}
+
+// The Content-Type header first, then the leading bytes; None for types too vague to name a file
+fn detected_extension(content_type: Option<&str>, bytes: &[u8]) -> Option<&'static str> {
+    let mime = content_type
+        .and_then(|value| value.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase());
+
+    let from_header = match mime.as_deref() {
+        Some("image/jpeg" | "image/jpg" | "image/pjpeg") => Some("jpg"),
+        Some("image/png") => Some("png"),
+        Some("image/gif") => Some("gif"),
+        Some("image/webp") => Some("webp"),
+        Some("image/avif") => Some("avif"),
+        Some("image/bmp") => Some("bmp"),
+        Some("image/svg+xml") => Some("svg"),
+        Some("image/x-icon" | "image/vnd.microsoft.icon") => Some("ico"),
+        Some("video/mp4") => Some("mp4"),
+        Some("video/webm") => Some("webm"),
+        Some("audio/mpeg") => Some("mp3"),
+        Some("application/pdf") => Some("pdf"),
+        _ => None,
+    };
+
+    from_header.or_else(|| infer::get(bytes).map(|kind| kind.extension()))
+}
+
+// Swaps the extension taken from the URL for the detected one, saying so when the URL named
+// another type rather than none
+fn retyped_path(url: &str, file_path: PathBuf, detected: &str) -> PathBuf {
+    let current = file_path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+    let same_type = |extension: &str| {
+        extension == detected || matches!((extension, detected), ("jpeg" | "jpe", "jpg"))
+    };
+
+    match current.as_deref() {
+        Some(extension) if same_type(extension) => file_path,
+        None | Some("bin") => file_path.with_extension(detected),
+        Some(extension) => {
+            let retyped = file_path.with_extension(detected);
+            eprintln!(
+                "Attachment {} is named .{} but looks like .{}, saving it as {}",
+                url,
+                extension,
+                detected,
+                retyped.display()
+            );
+            retyped
+        }
+    }
+}
+
+fn media_file_name(stem: &str, url: &str) -> String {
+    let extension = url
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, extension)| extension)
+        .filter(|extension| extension.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or("bin");
+
+    format!("{}.{}", stem, extension)
+}
+
+// Percent-decoded last path segment, with anything a file system might reject replaced
+fn url_file_name(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url).ok()?;
+    let segment = url.path_segments()?.next_back()?;
+
+    let mut bytes = vec![];
+    let mut rest = segment.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let decoded = tail
+            .get(..2)
+            .filter(|_| byte == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match decoded {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    sanitize_file_name(&String::from_utf8_lossy(&bytes))
+}
+
+// Single path component: separators and other characters a file system might reject become
+// `_`, and nothing is left that could climb out of the directory
+fn sanitize_file_name(name: &str) -> Option<String> {
+    let name = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>();
+
+    let name = name.trim();
+    (!name.is_empty() && name != "." && name != "..").then(|| name.to_string())
+}
+
+const PAGE_NAME_CHARS: usize = 60;
+
+// <category>/<id>-<slug>.html, falling back to the category id and a bare article id when a
+// title has nothing usable left; both are cut short so Korean titles stay within name limits
+fn grouped_page_path(args: &Arguments, article: &ArticleDataResponse) -> PathBuf {
+    let category = sanitize_file_name(localized(&article.category_titles, args.title_lang()))
+        .and_then(page_path_component)
+        .unwrap_or_else(|| article.category_id.to_string());
+
+    let slug =
+        sanitize_file_name(localized(&article.titles, args.title_lang())).and_then(|title| {
+            page_path_component(
+                title
+                    .to_lowercase()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join("-"),
+            )
+        });
+
+    let file_name = match slug {
+        Some(slug) => format!("{}-{}.html", article.id, slug),
+        None => format!("{}.html", article.id),
+    };
+
+    Path::new(&category).join(file_name)
+}
+
+// Cut to PAGE_NAME_CHARS, then without the trailing dots and spaces Windows silently drops; a name
+// made of dots alone, `..` included, has nothing left
+fn page_path_component(name: String) -> Option<String> {
+    let name = name.chars().take(PAGE_NAME_CHARS).collect::<String>();
+    let name = name.trim_end_matches(['.', ' ']);
+
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn is_not_found(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<reqwest::Error>()
+        .and_then(|e| e.status())
+        == Some(StatusCode::NOT_FOUND)
+}
+
+#[derive(Debug, Serialize)]
+struct BrokenLink {
+    article_id: i32,
+    kind: &'static str,
+    url: String,
+    status: String,
+}
+
+// HEAD requests through the same rate limiter and per-host permits as the crawl, a few in flight at once
+async fn validate_links(
+    crawler: &Crawler,
+    ko_articles: &[ArticleDataResponse],
+) -> anyhow::Result<()> {
+    let mut links = vec![];
+
+    for article in ko_articles {
+        if let Some(image_url) = article.image_url.as_deref().filter(|url| !url.is_empty()) {
+            links.push((article.id, "image", image_url.to_string()));
+        }
+
+        for attachment in article.attachments.values().flatten() {
+            if !attachment.source_url.is_empty() {
+                links.push((article.id, "attachment", attachment.source_url.clone()));
+            }
+        }
+
+        links.push((
+            article.id,
+            "article",
+            crawler
+                .args
+                .canonical_url(article.id)
+                .unwrap_or_else(|| article_url(&crawler.args.article_base_url, article.id)),
+        ));
+    }
+
+    let checked = links.len();
+    let mut broken = futures::stream::iter(links)
+        .map(|(article_id, kind, url)| async move {
+            let status = match check_link(crawler, &url).await {
+                Ok(status) if status.is_success() => return None,
+                // Not ours to check
+                Err(e) if e.is::<DisallowedByRobots>() => return None,
+                Ok(status) => status.to_string(),
+                Err(e) => format!("error: {}", e),
+            };
+
+            Some(BrokenLink {
+                article_id,
+                kind,
+                url,
+                status,
+            })
+        })
+        .buffer_unordered(8)
+        .filter_map(|link| async move { link })
+        .collect::<Vec<_>>()
+        .await;
+    broken.sort_by(|a, b| (a.article_id, &a.url).cmp(&(b.article_id, &b.url)));
+
+    let final_dir = crawler.args.output_dir.join("final");
+    let file_path = report::write(&crawler.args, &final_dir, "broken_links", &broken)?;
+
+    if !crawler.args.writes_to_stdout() {
+        println!(
+            "{} of {} links broken, listed in {}",
+            broken.len(),
+            checked,
+            file_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+// Some servers refuse HEAD outright, those get a GET instead
+// HEAD, or GET where the server refuses HEAD; retried like any other request, so a transient
+// 5xx only counts as broken once the retries ran out
+async fn check_link(crawler: &Crawler, url: &str) -> anyhow::Result<StatusCode> {
+    if crawler.args.respect_robots && !robots_allowed(crawler, url).await? {
+        return Err(DisallowedByRobots(url.to_string()).into());
+    }
+
+    let checked = with_retries(crawler, url, || async {
+        crawler.rate_limiter.until_ready().await;
+        let _permit = acquire_host_permit(crawler, url).await?;
+
+        let mut res = crawler.client.head(url).send().await?;
+        if res.status() == StatusCode::METHOD_NOT_ALLOWED {
+            res = crawler.client.get(url).send().await?;
+        }
+
+        Ok(res.error_for_status()?.status())
+    })
+    .await;
+
+    match checked {
+        Err(e) => match e.downcast_ref::<reqwest::Error>().and_then(|e| e.status()) {
+            Some(status) => Ok(status),
+            None => Err(e),
+        },
+        checked => checked,
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CategoryDiscrepancy {
+    category_id: i32,
+    title: String,
+    issue: &'static str,
+}
+
+// Tree categories without a single article anywhere below them, and categories named by articles
+// that the tree does not contain
+fn report_category_discrepancies(args: &Arguments, data: &CrawlData) -> anyhow::Result<()> {
+    // A JSON source or an id range carries no tree to hold the articles against
+    if data.categories.is_empty() {
+        eprintln!("No category tree to cross-check the article categories against");
+        return Ok(());
+    }
+
+    let article_categories = data
+        .ko_articles
+        .iter()
+        .map(|article| article.category_id)
+        .collect::<HashSet<_>>();
+
+    let mut tree_categories = HashSet::new();
+    let mut discrepancies = vec![];
+    find_empty_categories(
+        args,
+        &data.categories,
+        &article_categories,
+        &mut tree_categories,
+        &mut discrepancies,
+    );
+
+    let mut reported = HashSet::new();
+    for article in &data.ko_articles {
+        if !tree_categories.contains(&article.category_id) && reported.insert(article.category_id) {
+            discrepancies.push(CategoryDiscrepancy {
+                category_id: article.category_id,
+                title: localized(&article.category_titles, args.title_lang()).to_string(),
+                issue: "missing_from_tree",
+            });
+        }
+    }
+
+    let final_dir = args.output_dir.join("final");
+    let file_path = report::write(args, &final_dir, "category_discrepancies", &discrepancies)?;
+
+    if !args.writes_to_stdout() {
+        println!(
+            "{} category discrepancies written to {}",
+            discrepancies.len(),
+            file_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+// Returns whether any article sits in one of the given categories or below them
+fn find_empty_categories(
+    args: &Arguments,
+    children: &[CategoryChildResponse],
+    article_categories: &HashSet<i32>,
+    tree_categories: &mut HashSet<i32>,
+    discrepancies: &mut Vec<CategoryDiscrepancy>,
+) -> bool {
+    let mut any_articles = false;
+
+    for child in children.iter().filter(|child| child.type_ == "CATEGORY") {
+        tree_categories.insert(child.id);
+
+        let below = find_empty_categories(
+            args,
+            &child.children,
+            article_categories,
+            tree_categories,
+            discrepancies,
+        );
+        let has_articles = below || article_categories.contains(&child.id);
+
+        if !has_articles {
+            discrepancies.push(CategoryDiscrepancy {
+                category_id: child.id,
+                title: localized(&child.titles, args.title_lang()).to_string(),
+                issue: "no_articles",
+            });
+        }
+
+        any_articles |= has_articles;
+    }
+
+    any_articles
+}
+
+fn write_missing_images_report(
+    output_dir: &Path,
+    newline: Newline,
+    ko_articles: &[ArticleDataResponse],
+    attachment_manifest: &[AttachmentManifestEntry],
+) -> anyhow::Result<()> {
+    let mut lines = vec![];
+
+    for article in ko_articles {
+        let title = &article.titles[&LangEnum::KR];
+
+        if article.image_url.is_none() {
+            lines.push(format!("{}\t{}\tno image_url", article.id, title));
+        }
+
+        for entry in attachment_manifest
+            .iter()
+            .filter(|entry| entry.article_id == article.id && entry.status == "not_found")
+        {
+            lines.push(format!(
+                "{}\t{}\tattachment 404: {}",
+                article.id, title, entry.source_url
+            ));
+        }
+    }
+
+    let final_dir = output_dir.join("final");
+    std::fs::create_dir_all(final_dir.clone()).unwrap();
+
+    write_atomic(
+        &final_dir.join("missing_images.txt"),
+        &newline.apply(lines.join("\n").as_bytes()),
+    )?;
+
+    println!(
+        "{} missing-image entries written to missing_images.txt",
+        lines.len()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn category(id: i32, position: i32, title: &str, children: &str) -> String {
+        format!(
+            r#"{{"id": {id}, "parent_id": null, "position": {position}, "type": "CATEGORY", "status": "PUBLISHED", "titles": {{"KR": "{title}"}}, "children": [{children}], "modified": false}}"#
+        )
+    }
+
+    #[test]
+    fn category_ranks_sort_siblings_by_position_before_flattening() {
+        // A nested position says nothing about the categories outside its parent
+        let tree = format!(
+            "[{}, {}]",
+            category(1, 1, "가", &category(3, 0, "다", "")),
+            category(2, 0, "나", &category(4, 5, "라", "")),
+        );
+        let categories = serde_json::from_str::<Vec<CategoryChildResponse>>(&tree).unwrap();
+
+        let mut ranks = HashMap::new();
+        collect_category_ranks(&categories, CategorySort::Position, &mut ranks);
+        let mut names = ranks.into_iter().collect::<Vec<_>>();
+        names.sort_by_key(|(_, rank)| *rank);
+        assert_eq!(
+            names.into_iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            ["나", "라", "가", "다"]
+        );
+
+        let mut ranks = HashMap::new();
+        collect_category_ranks(&categories, CategorySort::Id, &mut ranks);
+        assert_eq!(ranks["다"], 3);
+        assert_eq!(ranks["라"], 4);
+    }
+
+    #[test]
+    fn detected_extension_prefers_the_header_over_the_bytes() {
+        const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+        assert_eq!(detected_extension(Some("image/jpeg"), PNG), Some("jpg"));
+        assert_eq!(
+            detected_extension(Some("Image/WebP; charset=binary"), b""),
+            Some("webp")
+        );
+        assert_eq!(
+            detected_extension(Some("image/svg+xml"), b"<svg/>"),
+            Some("svg")
+        );
+        // Too vague a header falls back to sniffing the bytes
+        assert_eq!(
+            detected_extension(Some("application/octet-stream"), PNG),
+            Some("png")
+        );
+        assert_eq!(detected_extension(None, PNG), Some("png"));
+        assert_eq!(detected_extension(None, b"plain text"), None);
+    }
+
+    #[test]
+    fn retyped_path_keeps_matching_extensions_and_swaps_the_rest() {
+        let retype = |name: &str, detected: &str| {
+            retyped_path("https://example.com/a", PathBuf::from(name), detected)
+        };
+
+        assert_eq!(retype("a/1.png", "png"), PathBuf::from("a/1.png"));
+        assert_eq!(retype("a/1.PNG", "png"), PathBuf::from("a/1.PNG"));
+        assert_eq!(retype("a/1.jpeg", "jpg"), PathBuf::from("a/1.jpeg"));
+        assert_eq!(retype("a/1.jpe", "jpg"), PathBuf::from("a/1.jpe"));
+        assert_eq!(retype("a/1.bin", "webp"), PathBuf::from("a/1.webp"));
+        assert_eq!(retype("a/1", "gif"), PathBuf::from("a/1.gif"));
+        assert_eq!(retype("a/1.jpg", "png"), PathBuf::from("a/1.png"));
+    }
+
+    #[test]
+    fn claim_path_tells_a_taken_name_apart_by_the_stem() {
+        let mut used_paths = HashSet::new();
+
+        let source = claim_path(&mut used_paths, PathBuf::from("7/photo.png"), "1");
+        let thumbnail = claim_path(&mut used_paths, PathBuf::from("7/photo.png"), "1_thumb");
+        let bare = claim_path(&mut used_paths, PathBuf::from("7/photo"), "2");
+        let bare_again = claim_path(&mut used_paths, PathBuf::from("7/photo"), "3");
+
+        assert_eq!(source, PathBuf::from("7/photo.png"));
+        assert_eq!(thumbnail, PathBuf::from("7/photo_1_thumb.png"));
+        assert_eq!(bare, PathBuf::from("7/photo"));
+        assert_eq!(bare_again, PathBuf::from("7/photo_3"));
+    }
+
+    #[test]
+    fn existing_candidates_find_retyped_and_suffixed_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "photo.webp",
+            "photo_1_thumb.png",
+            "photo_2.png",
+            "other.jpg",
+        ] {
+            std::fs::write(dir.path().join(name), b"bytes").unwrap();
+        }
+
+        let existing_files = files_by_stem(dir.path());
+        let candidates =
+            existing_candidates(&existing_files, &dir.path().join("photo.jpg"), "1_thumb");
+        assert_eq!(
+            candidates,
+            ["photo.jpg", "photo.webp", "photo_1_thumb.png"].map(|name| dir.path().join(name))
+        );
+
+        // An article's own directory is listed too, and only its copies count for its files
+        std::fs::create_dir(dir.path().join("7")).unwrap();
+        std::fs::write(dir.path().join("7").join("photo.gif"), b"bytes").unwrap();
+        let existing_files = files_by_stem(dir.path());
+        let candidates =
+            existing_candidates(&existing_files, &dir.path().join("7").join("photo"), "1");
+        assert_eq!(
+            candidates,
+            ["7/photo", "7/photo.gif"].map(|name| dir.path().join(name))
+        );
+    }
+
+    fn page_path(category: &str, title: &str) -> PathBuf {
+        let article = serde_json::from_value::<ArticleDataResponse>(serde_json::json!({
+            "id": 5,
+            "category_id": 1,
+            "category_titles": {"KR": category},
+            "status": "PUBLISHED",
+            "titles": {"KR": title},
+            "subtitles": {"KR": ""},
+            "image_url": null,
+            "attachments": {},
+            "contents": {"KR": "본문"},
+        }))
+        .unwrap();
+
+        grouped_page_path(&Arguments::parse_from(["dfu_crawler"]), &article)
+    }
+
+    #[test]
+    fn grouped_page_path_keeps_separators_out_of_the_components() {
+        assert_eq!(
+            page_path("상위/하위", "A Title\\With: Parts"),
+            PathBuf::from("상위_하위/5-a-title_with_-parts.html")
+        );
+        assert_eq!(
+            page_path("분류", "what? *really*"),
+            PathBuf::from("분류/5-what_-_really_.html")
+        );
+    }
+
+    #[test]
+    fn grouped_page_path_falls_back_on_dots_and_trailing_spaces() {
+        assert_eq!(page_path("..", ".."), PathBuf::from("1/5.html"));
+        assert_eq!(page_path("...", "..."), PathBuf::from("1/5.html"));
+        assert_eq!(
+            page_path("v1.0. ", "ends with a dot."),
+            PathBuf::from("v1.0/5-ends-with-a-dot.html")
+        );
+    }
+
+    #[test]
+    fn grouped_page_path_cuts_long_titles_short() {
+        let category = "가".repeat(70);
+        let title = format!("{}.b", "a".repeat(59));
+
+        assert_eq!(
+            page_path(&category, &title),
+            Path::new(&"가".repeat(PAGE_NAME_CHARS)).join(format!("5-{}.html", "a".repeat(59)))
+        );
+        assert_eq!(
+            page_path("분류", &"나".repeat(100)),
+            PathBuf::from(format!("분류/5-{}.html", "나".repeat(PAGE_NAME_CHARS)))
+        );
+    }
+
+    #[test]
+    fn expand_output_dir_fills_in_the_placeholders() {
+        let now = chrono::Local.with_ymd_and_hms(2024, 3, 5, 7, 8, 9).unwrap();
+        let expand = |template: &str| expand_output_dir(Path::new(template), now).unwrap();
+
+        assert_eq!(expand("crawled_data"), PathBuf::from("crawled_data"));
+        assert_eq!(expand("runs/{date}"), PathBuf::from("runs/2024-03-05"));
+        assert_eq!(expand("runs/{time}"), PathBuf::from("runs/07-08-09"));
+        assert_eq!(
+            expand("runs/{datetime}"),
+            PathBuf::from("runs/2024-03-05_07-08-09")
+        );
+        assert_eq!(expand("runs/{%Y%m}/x"), PathBuf::from("runs/202403/x"));
+        assert_eq!(expand("{date}-{%H}h"), PathBuf::from("2024-03-05-07h"));
+    }
+
+    #[test]
+    fn expand_output_dir_rejects_bad_placeholders() {
+        let now = chrono::Local::now();
+        let error = |template: &str| {
+            expand_output_dir(Path::new(template), now)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            error("runs/{date"),
+            "Unclosed placeholder in --output-dir runs/{date"
+        );
+        assert_eq!(
+            error("runs/{week}"),
+            "Unknown placeholder {week} in --output-dir"
+        );
+        assert_eq!(
+            error("runs/{%Q}"),
+            "Invalid strftime format {%Q} in --output-dir"
+        );
+    }
+}
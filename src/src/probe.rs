@@ -0,0 +1,199 @@
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+// Strict parse first; when that fails the payload is re-read as loose JSON and held against
+// the schema of T, so a renamed upstream field shows up by name instead of as a bare serde error
+pub fn parse<T: DeserializeOwned + JsonSchema>(body: &str) -> anyhow::Result<T> {
+    parse_with(body, false)
+}
+
+// With `deny_unknown`, fields the schema of T does not declare fail the parse too, the way
+// #[serde(deny_unknown_fields)] would, instead of being dropped silently
+pub fn parse_with<T: DeserializeOwned + JsonSchema>(
+    body: &str,
+    deny_unknown: bool,
+) -> anyhow::Result<T> {
+    // Windows tools like to save JSON with a BOM, which serde_json rejects as a stray character;
+    // a CDN may also pad the body, with the BOM after the padding rather than before it
+    let body = body.trim_start_matches(|c: char| c == '\u{feff}' || c.is_whitespace());
+
+    if deny_unknown {
+        let payload = serde_json::from_str::<Value>(body)?;
+        let schema = schemars::schema_for!(T);
+        let mut unknown = vec![];
+        unknown_fields(
+            schema.as_value(),
+            schema.as_value(),
+            &payload,
+            "",
+            &mut unknown,
+        );
+
+        anyhow::ensure!(
+            unknown.is_empty(),
+            "The response has fields this version does not know (--parse-strict): {}",
+            unknown.join(", ")
+        );
+    }
+
+    let error = match serde_json::from_str(body) {
+        Ok(value) => return Ok(value),
+        Err(e) => e,
+    };
+
+    let Ok(payload) = serde_json::from_str::<Value>(body) else {
+        return Err(error.into());
+    };
+
+    let schema = schemars::schema_for!(T);
+    let mut missing = vec![];
+    missing_fields(
+        schema.as_value(),
+        schema.as_value(),
+        &payload,
+        "",
+        &mut missing,
+    );
+
+    if missing.is_empty() {
+        return Err(error.into());
+    }
+
+    Err(anyhow::anyhow!(
+        "The response shape changed upstream, missing fields: {} ({})",
+        missing.join(", "),
+        error
+    ))
+}
+
+// Array indices collapse to `[]`, so a field dropped from every child is reported once
+fn missing_fields(
+    root: &Value,
+    schema: &Value,
+    payload: &Value,
+    path: &str,
+    missing: &mut Vec<String>,
+) {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        if let Some(target) = reference.strip_prefix('#').and_then(|p| root.pointer(p)) {
+            missing_fields(root, target, payload, path, missing);
+        }
+        return;
+    }
+
+    // Option<T> becomes an anyOf with null
+    for key in ["anyOf", "oneOf"] {
+        for variant in schema
+            .get(key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            missing_fields(root, variant, payload, path, missing);
+        }
+    }
+
+    match payload {
+        Value::Object(object) => {
+            let required = schema.get("required").and_then(Value::as_array);
+            for field in required.into_iter().flatten().filter_map(Value::as_str) {
+                let field_path = join(path, field);
+
+                if !object.contains_key(field) && !missing.contains(&field_path) {
+                    missing.push(field_path);
+                }
+            }
+
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (field, property) in properties.into_iter().flatten() {
+                if let Some(value) = object.get(field) {
+                    missing_fields(root, property, value, &join(path, field), missing);
+                }
+            }
+
+            // Maps such as the per-language titles
+            if let Some(values) = schema.get("additionalProperties").filter(|v| v.is_object()) {
+                for (key, value) in object {
+                    missing_fields(root, values, value, &join(path, key), missing);
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for item in items {
+                    missing_fields(root, item_schema, item, &format!("{}[]", path), missing);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// Walks the payload like missing_fields, but collects the keys of structs that have no property
+// for them; maps (additionalProperties) take any key
+fn unknown_fields(
+    root: &Value,
+    schema: &Value,
+    payload: &Value,
+    path: &str,
+    unknown: &mut Vec<String>,
+) {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        if let Some(target) = reference.strip_prefix('#').and_then(|p| root.pointer(p)) {
+            unknown_fields(root, target, payload, path, unknown);
+        }
+        return;
+    }
+
+    for key in ["anyOf", "oneOf"] {
+        for variant in schema
+            .get(key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            unknown_fields(root, variant, payload, path, unknown);
+        }
+    }
+
+    match payload {
+        Value::Object(object) => {
+            if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                for (field, value) in object {
+                    let field_path = join(path, field);
+
+                    match properties.get(field) {
+                        Some(property) => {
+                            unknown_fields(root, property, value, &field_path, unknown)
+                        }
+                        None if !unknown.contains(&field_path) => unknown.push(field_path),
+                        None => {}
+                    }
+                }
+            }
+
+            if let Some(values) = schema.get("additionalProperties").filter(|v| v.is_object()) {
+                for (key, value) in object {
+                    unknown_fields(root, values, value, &join(path, key), unknown);
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for item in items {
+                    unknown_fields(root, item_schema, item, &format!("{}[]", path), unknown);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn join(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+
+use crate::{
+    collect_article_ids, doctor::cached_article_ids, format_count, get_category_response,
+    validators_path, Arguments, Crawler,
+};
+
+// Deletes cached articles the current category tree no longer lists, with their validator sidecars
+pub async fn run(args: &Arguments, dry_run: bool) -> anyhow::Result<()> {
+    args.check_site_urls()?;
+    let crawler = Crawler::new(args, HashSet::new())?;
+    let categories = get_category_response(&crawler, &args.categories_url).await?;
+
+    let mut listed_ids = HashSet::new();
+    collect_article_ids(&categories.data, &mut listed_ids);
+
+    // An empty listing is far more likely a broken response than an emptied site
+    anyhow::ensure!(
+        !listed_ids.is_empty(),
+        "The category tree lists no articles, refusing to prune the whole cache"
+    );
+
+    let articles_dir = args.output_dir.join("articles");
+    let mut orphaned = cached_article_ids(&articles_dir)
+        .difference(&listed_ids)
+        .copied()
+        .collect::<Vec<_>>();
+    orphaned.sort_unstable();
+
+    for id in &orphaned {
+        let file_path = articles_dir.join(format!("{}.json", id));
+
+        if dry_run {
+            println!("Would remove {}", file_path.display());
+            continue;
+        }
+
+        std::fs::remove_file(&file_path)?;
+        let validators_path = validators_path(&file_path);
+        if validators_path.exists() {
+            std::fs::remove_file(validators_path)?;
+        }
+
+        println!("Removed {}", file_path.display());
+    }
+
+    println!(
+        "{} cached articles are no longer listed{}",
+        format_count(args.locale, orphaned.len()),
+        if dry_run { ", nothing was removed" } else { "" }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,518 @@
+use std::{
+    iter::{Enumerate, Peekable},
+    str::Chars,
+};
+
+use crate::{export::ContentsFormat, localized, normalize_title, ArticleDataResponse, LangEnum};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Id,
+    Status,
+    Category,
+    ContentLen,
+    HasImage,
+}
+
+impl Field {
+    const ALL: [Field; 5] = [
+        Field::Id,
+        Field::Status,
+        Field::Category,
+        Field::ContentLen,
+        Field::HasImage,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::Id => "id",
+            Field::Status => "status",
+            Field::Category => "category",
+            Field::ContentLen => "content_len",
+            Field::HasImage => "has_image",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|field| field.name() == name)
+    }
+
+    fn value(
+        self,
+        article: &ArticleDataResponse,
+        lang: &LangEnum,
+        contents_format: ContentsFormat,
+    ) -> Value {
+        match self {
+            Field::Id => Value::Number(article.id as i64),
+            Field::Status => Value::Text(article.status.to_lowercase()),
+            Field::Category => Value::Text(
+                normalize_title(localized(&article.category_titles, &LangEnum::KR)).to_lowercase(),
+            ),
+            // The text the exports show, without any markup
+            Field::ContentLen => Value::Number(
+                contents_format
+                    .to_text(localized(&article.contents, lang))
+                    .chars()
+                    .count() as i64,
+            ),
+            Field::HasImage => Value::Bool(
+                article
+                    .image_url
+                    .as_deref()
+                    .is_some_and(|url| !url.is_empty())
+                    || article
+                        .attachments
+                        .values()
+                        .flatten()
+                        .any(|attachment| attachment.type_ == "IMAGE"),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(i64),
+    // Lowercased, text compares ignore case
+    Text(String),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone)]
+pub enum Filter {
+    Or(Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Compare(Field, Op, Value),
+}
+
+impl Filter {
+    pub fn matches(
+        &self,
+        article: &ArticleDataResponse,
+        lang: &LangEnum,
+        contents_format: ContentsFormat,
+    ) -> bool {
+        match self {
+            Filter::Or(left, right) => {
+                left.matches(article, lang, contents_format)
+                    || right.matches(article, lang, contents_format)
+            }
+            Filter::And(left, right) => {
+                left.matches(article, lang, contents_format)
+                    && right.matches(article, lang, contents_format)
+            }
+            Filter::Not(inner) => !inner.matches(article, lang, contents_format),
+            Filter::Compare(field, op, expected) => {
+                let actual = field.value(article, lang, contents_format);
+
+                match (op, &actual, expected) {
+                    (Op::Eq, _, _) => actual == *expected,
+                    (Op::Ne, _, _) => actual != *expected,
+                    (op, Value::Number(actual), Value::Number(expected)) => match op {
+                        Op::Lt => actual < expected,
+                        Op::Le => actual <= expected,
+                        Op::Gt => actual > expected,
+                        _ => actual >= expected,
+                    },
+                    // Ruled out while parsing
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+// How many parentheses and ! may nest, so a hostile expression cannot run the parser (or
+// matching the filter it returns) out of stack
+const MAX_DEPTH: usize = 64;
+
+// E.g. `status == published && content_len > 500`, with ||, ! and parentheses; fields are
+// id, status, category, content_len and has_image, text values may be quoted
+pub fn parse(expr: &str) -> Result<Filter, String> {
+    let mut parser = Parser {
+        tokens: tokenize(expr)?,
+        next: 0,
+        depth: 0,
+    };
+
+    let filter = parser.or()?;
+    match parser.peek() {
+        None => Ok(filter),
+        Some(_) => Err(format!(
+            "unexpected input at position {}",
+            parser.position()
+        )),
+    }
+}
+
+fn tokenize(expr: &str) -> Result<Vec<(usize, Token)>, String> {
+    let mut tokens = vec![];
+    // Positions count characters, not bytes, so they line up with what was typed
+    let mut chars = expr.chars().enumerate().peekable();
+
+    while let Some((position, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '&' | '|' => match chars.next_if(|(_, next)| *next == c) {
+                Some(_) if c == '&' => Token::And,
+                Some(_) => Token::Or,
+                None => return Err(format!("expected {}{} at position {}", c, c, position + 1)),
+            },
+            '=' => match chars.next_if(|(_, next)| *next == '=') {
+                Some(_) => Token::Op(Op::Eq),
+                None => return Err(format!("expected == at position {}", position + 1)),
+            },
+            '!' => match chars.next_if(|(_, next)| *next == '=') {
+                Some(_) => Token::Op(Op::Ne),
+                None => Token::Not,
+            },
+            '<' | '>' => {
+                let or_equal = chars.next_if(|(_, next)| *next == '=').is_some();
+                Token::Op(match (c, or_equal) {
+                    ('<', false) => Op::Lt,
+                    ('<', true) => Op::Le,
+                    ('>', false) => Op::Gt,
+                    _ => Op::Ge,
+                })
+            }
+            '"' | '\'' => Token::Quoted(quoted(&mut chars, c, position)?),
+            c if is_word_char(c) => {
+                let mut word = c.to_string();
+                while let Some((_, next)) = chars.next_if(|(_, next)| is_word_char(*next)) {
+                    word.push(next);
+                }
+                Token::Word(word)
+            }
+            c => {
+                return Err(format!(
+                    "unexpected character {:?} at position {}",
+                    c,
+                    position + 1
+                ))
+            }
+        };
+
+        tokens.push((position, token));
+    }
+
+    Ok(tokens)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+fn quoted(
+    chars: &mut Peekable<Enumerate<Chars>>,
+    quote: char,
+    position: usize,
+) -> Result<String, String> {
+    let mut text = String::new();
+
+    for (_, c) in chars.by_ref() {
+        if c == quote {
+            return Ok(text);
+        }
+        text.push(c);
+    }
+
+    Err(format!("unterminated string at position {}", position + 1))
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn advance(&mut self) -> Option<(usize, Token)> {
+        let token = self.tokens.get(self.next).cloned()?;
+        self.next += 1;
+        Some(token)
+    }
+
+    // 1-based, of the token at hand; only called while there is one
+    fn position(&self) -> usize {
+        self.tokens[self.next].0 + 1
+    }
+
+    fn error(&self, expected: &str) -> String {
+        match self.peek() {
+            None => format!("expected {} at the end of the expression", expected),
+            Some(_) => format!("expected {} at position {}", expected, self.position()),
+        }
+    }
+
+    fn or(&mut self) -> Result<Filter, String> {
+        let mut filter = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.advance();
+            filter = Filter::Or(Box::new(filter), Box::new(self.and()?));
+        }
+        Ok(filter)
+    }
+
+    fn and(&mut self) -> Result<Filter, String> {
+        let mut filter = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.advance();
+            filter = Filter::And(Box::new(filter), Box::new(self.unary()?));
+        }
+        Ok(filter)
+    }
+
+    fn unary(&mut self) -> Result<Filter, String> {
+        if !matches!(self.peek(), Some(Token::Not | Token::Open)) {
+            return self.comparison();
+        }
+
+        if self.depth == MAX_DEPTH {
+            return Err(format!(
+                "nested more than {} levels deep at position {}",
+                MAX_DEPTH,
+                self.position()
+            ));
+        }
+        self.depth += 1;
+
+        let filter = match self.advance() {
+            Some((_, Token::Not)) => self.unary().map(|inner| Filter::Not(Box::new(inner))),
+            _ => self.or().and_then(|filter| match self.peek() {
+                Some(Token::Close) => {
+                    self.advance();
+                    Ok(filter)
+                }
+                _ => Err(self.error(")")),
+            }),
+        };
+
+        self.depth -= 1;
+        filter
+    }
+
+    fn comparison(&mut self) -> Result<Filter, String> {
+        let field = match self.peek() {
+            Some(Token::Word(name)) => Field::from_name(name).ok_or_else(|| {
+                format!(
+                    "unknown field {} at position {}, expected one of {}",
+                    name,
+                    self.position(),
+                    Field::ALL.map(Field::name).join(", ")
+                )
+            })?,
+            _ => return Err(self.error("a field name")),
+        };
+        self.advance();
+
+        let op = match self.peek() {
+            Some(Token::Op(op)) => *op,
+            // A bare flag such as `has_image`
+            _ if field == Field::HasImage => {
+                return Ok(Filter::Compare(field, Op::Eq, Value::Bool(true)))
+            }
+            _ => return Err(self.error("a comparison operator")),
+        };
+        self.advance();
+
+        let position = match self.peek() {
+            Some(Token::Word(_) | Token::Quoted(_)) => self.position(),
+            _ => return Err(self.error("a value")),
+        };
+        let (text, quoted) = match self.advance() {
+            Some((_, Token::Word(text))) => (text, false),
+            Some((_, Token::Quoted(text))) => (text, true),
+            _ => unreachable!(),
+        };
+
+        let value = match field {
+            Field::Id | Field::ContentLen => match text.parse() {
+                Ok(number) if !quoted => Value::Number(number),
+                _ => return Err(format!("expected a number at position {}", position)),
+            },
+            Field::Status | Field::Category => Value::Text(match field {
+                Field::Category => normalize_title(&text).to_lowercase(),
+                _ => text.to_lowercase(),
+            }),
+            Field::HasImage => match text.as_str() {
+                "true" if !quoted => Value::Bool(true),
+                "false" if !quoted => Value::Bool(false),
+                _ => return Err(format!("expected true or false at position {}", position)),
+            },
+        };
+
+        let ordered = matches!(op, Op::Lt | Op::Le | Op::Gt | Op::Ge);
+        if ordered && !matches!(value, Value::Number(_)) {
+            return Err(format!(
+                "{} can only be compared with == or !=",
+                field.name()
+            ));
+        }
+
+        Ok(Filter::Compare(field, op, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(
+        id: i32,
+        category: &str,
+        contents: &str,
+        image_url: Option<&str>,
+    ) -> ArticleDataResponse {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "category_id": 1,
+            "category_titles": {"KR": category},
+            "status": "PUBLISHED",
+            "titles": {"KR": "제목"},
+            "subtitles": {"KR": ""},
+            "image_url": image_url,
+            "attachments": {},
+            "contents": {"KR": contents},
+        }))
+        .unwrap()
+    }
+
+    fn matches(expr: &str, article: &ArticleDataResponse) -> bool {
+        parse(expr)
+            .unwrap()
+            .matches(article, &LangEnum::KR, ContentsFormat::Auto)
+    }
+
+    fn error(expr: &str) -> String {
+        parse(expr).unwrap_err()
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let first = article(1, "분류", "본문", None);
+
+        assert!(matches("id == 1 || id == 2 && status == draft", &first));
+        assert!(!matches("(id == 1 || id == 2) && status == draft", &first));
+        assert!(matches("status == draft && id == 2 || id == 1", &first));
+    }
+
+    #[test]
+    fn negation_applies_to_the_closest_term() {
+        let first = article(1, "분류", "본문", None);
+
+        assert!(!matches("!id == 1", &first));
+        assert!(matches("!!id == 1", &first));
+        assert!(matches("!id == 2 && id == 1", &first));
+        assert!(matches("!(id == 2 || has_image)", &first));
+        assert!(matches("!has_image", &first));
+        assert!(matches("id != 2", &first));
+    }
+
+    #[test]
+    fn quoted_values_keep_their_spaces_and_ignore_case() {
+        let first = article(1, "첫  분류", "본문", Some("https://example.com/a.png"));
+
+        assert!(matches(r#"category == "첫 분류""#, &first));
+        assert!(matches("category == '첫 분류'", &first));
+        assert!(matches("status == 'Published'", &first));
+        assert!(matches("status == \"a && b\" || has_image == true", &first));
+        assert!(matches("has_image", &first));
+    }
+
+    #[test]
+    fn content_len_counts_the_text_without_markup() {
+        let first = article(1, "분류", "<p>본문</p>", None);
+        let filter = parse("content_len == 2").unwrap();
+
+        assert!(filter.matches(&first, &LangEnum::KR, ContentsFormat::Auto));
+        assert!(!filter.matches(&first, &LangEnum::KR, ContentsFormat::Md));
+    }
+
+    #[test]
+    fn every_error_names_what_went_wrong_and_where() {
+        assert_eq!(error("id == 1 & id == 2"), "expected && at position 9");
+        assert_eq!(error("id == 1 | id == 2"), "expected || at position 9");
+        assert_eq!(error("id = 1"), "expected == at position 4");
+        assert_eq!(
+            error("id == 1 ; id == 2"),
+            "unexpected character ';' at position 9"
+        );
+        assert_eq!(
+            error("status == 'draft"),
+            "unterminated string at position 11"
+        );
+        assert_eq!(error("id == 1 id == 2"), "unexpected input at position 9");
+        assert_eq!(error("(id == 1"), "expected ) at the end of the expression");
+        assert_eq!(error("(id == 1 has_image"), "expected ) at position 10");
+        assert_eq!(error("== 1"), "expected a field name at position 1");
+        assert_eq!(
+            error("size > 1"),
+            "unknown field size at position 1, expected one of id, status, category, content_len, has_image"
+        );
+        assert_eq!(
+            error("status published"),
+            "expected a comparison operator at position 8"
+        );
+        assert_eq!(
+            error("id =="),
+            "expected a value at the end of the expression"
+        );
+        assert_eq!(error("id == '5'"), "expected a number at position 7");
+        assert_eq!(error("id == five"), "expected a number at position 7");
+        assert_eq!(
+            error("has_image == yes"),
+            "expected true or false at position 14"
+        );
+        assert_eq!(
+            error("status > draft"),
+            "status can only be compared with == or !="
+        );
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let first = article(1, "분류", "본문", None);
+        let nested = |depth: usize| format!("{}id == 1{}", "(".repeat(depth), ")".repeat(depth));
+
+        assert!(matches(&nested(MAX_DEPTH), &first));
+        assert_eq!(
+            error(&nested(MAX_DEPTH + 1)),
+            format!(
+                "nested more than {} levels deep at position {}",
+                MAX_DEPTH,
+                MAX_DEPTH + 1
+            )
+        );
+        assert!(error(&"!".repeat(100_000)).starts_with("nested more than"));
+    }
+}
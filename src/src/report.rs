@@ -0,0 +1,67 @@
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{write_atomic, Arguments};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+// JSON and CSV go to stdout as they are, `table` leaves the layout to the report itself
+pub fn print<T: Serialize>(
+    args: &Arguments,
+    rows: &[T],
+    table: impl FnOnce(),
+) -> anyhow::Result<()> {
+    match args.report_format {
+        ReportFormat::Table => table(),
+        ReportFormat::Json => println!("{}", serde_json::to_string_pretty(rows)?),
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(std::io::stdout());
+            for row in rows {
+                writer.serialize(row)?;
+            }
+            writer.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+// Reports written next to the exports: <name>.json for json, <name>.csv otherwise, since a
+// table has no file form of its own
+pub fn write<T: Serialize>(
+    args: &Arguments,
+    dir: &Path,
+    name: &str,
+    rows: &[T],
+) -> anyhow::Result<PathBuf> {
+    std::fs::create_dir_all(dir).unwrap();
+
+    let (file_path, body) = match args.report_format {
+        ReportFormat::Json => (
+            dir.join(format!("{}.json", name)),
+            serde_json::to_vec_pretty(rows)?,
+        ),
+        ReportFormat::Table | ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(vec![]);
+            for row in rows {
+                writer.serialize(row)?;
+            }
+
+            (
+                dir.join(format!("{}.csv", name)),
+                args.newline.apply(&writer.into_inner()?).into_owned(),
+            )
+        }
+    };
+
+    write_atomic(&file_path, &body)?;
+
+    Ok(file_path)
+}
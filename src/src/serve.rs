@@ -0,0 +1,227 @@
+use std::{collections::HashMap, convert::Infallible, net::SocketAddr, sync::Arc};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+
+use crate::{
+    export::{escape_html, html_attachments, snippet, ContentsFormat},
+    localized, Arguments, ArticleDataResponse, CategoryChildResponse, CrawlData, LangEnum,
+};
+
+const SEARCH_SNIPPET_LENGTH: usize = 160;
+
+struct Site {
+    categories: Vec<CategoryChildResponse>,
+    articles: HashMap<i32, ArticleDataResponse>,
+    // Ids in archive order, for the index when there is no category tree
+    article_ids: Vec<i32>,
+    lang: LangEnum,
+    title_lang: LangEnum,
+    contents_format: ContentsFormat,
+}
+
+// Pages are rendered per request straight from the loaded archive, nothing is written to disk
+pub async fn run(args: &Arguments, data: CrawlData, port: u16) -> anyhow::Result<()> {
+    let site = Arc::new(Site {
+        categories: data.categories,
+        article_ids: data.ko_articles.iter().map(|article| article.id).collect(),
+        articles: data
+            .ko_articles
+            .into_iter()
+            .map(|article| (article.id, article))
+            .collect(),
+        lang: args.lang.clone(),
+        title_lang: args.title_lang().clone(),
+        contents_format: args.contents_format,
+    });
+
+    let make_service = make_service_fn(move |_| {
+        let site = site.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let site = site.clone();
+                async move { Ok::<_, Infallible>(site.respond(&request)) }
+            }))
+        }
+    });
+
+    // Loopback only, the archive is not meant to be published from here
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let server = Server::try_bind(&address)?.serve(make_service);
+
+    println!(
+        "Serving {} on http://{}",
+        args.output_dir.display(),
+        address
+    );
+
+    server
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await?;
+
+    Ok(())
+}
+
+impl Site {
+    fn respond(&self, request: &Request<Body>) -> Response<Body> {
+        if request.method() != Method::GET {
+            return page(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed", "");
+        }
+
+        let path = request.uri().path();
+        let article = path
+            .strip_prefix("/articles/")
+            .and_then(|id| id.parse::<i32>().ok())
+            .and_then(|id| self.articles.get(&id));
+
+        match (path, article) {
+            ("/", _) => page(StatusCode::OK, "Archive", &self.index()),
+            ("/search", _) => {
+                let query = search_query(request).unwrap_or_default();
+                page(StatusCode::OK, "Search", &self.search(&query))
+            }
+            (_, Some(article)) => page(
+                StatusCode::OK,
+                localized(&article.titles, &self.title_lang),
+                &self.article(article),
+            ),
+            _ => page(StatusCode::NOT_FOUND, "Not found", "<p>Not found</p>\n"),
+        }
+    }
+
+    fn index(&self) -> String {
+        let mut body = search_form("");
+
+        if self.categories.is_empty() {
+            body.push_str("<ul>\n");
+            for id in &self.article_ids {
+                body.push_str(&self.article_link(&self.articles[id]));
+            }
+            body.push_str("</ul>\n");
+        } else {
+            self.push_tree(&self.categories, &mut body);
+        }
+
+        body
+    }
+
+    fn push_tree(&self, children: &[CategoryChildResponse], body: &mut String) {
+        body.push_str("<ul>\n");
+
+        for child in children {
+            if child.type_ == "ARTICLE" {
+                // Listed in the tree but not in the archive, e.g. filtered out during the crawl
+                if let Some(article) = self.articles.get(&child.id) {
+                    body.push_str(&self.article_link(article));
+                }
+                continue;
+            }
+
+            body.push_str(&format!(
+                "<li>{}\n",
+                escape_html(localized(&child.titles, &self.title_lang))
+            ));
+            self.push_tree(&child.children, body);
+            body.push_str("</li>\n");
+        }
+
+        body.push_str("</ul>\n");
+    }
+
+    fn article_link(&self, article: &ArticleDataResponse) -> String {
+        format!(
+            "<li><a href=\"/articles/{}\">{}</a></li>\n",
+            article.id,
+            escape_html(localized(&article.titles, &self.title_lang))
+        )
+    }
+
+    fn article(&self, article: &ArticleDataResponse) -> String {
+        let subtitle = localized(&article.subtitles, &self.lang).trim();
+
+        format!(
+            "<p><a href=\"/\">Archive</a> / {}</p>\n<h1>{}</h1>\n{}<div class=\"content\">{}</div>\n{}",
+            escape_html(localized(&article.category_titles, &self.title_lang)),
+            escape_html(localized(&article.titles, &self.title_lang)),
+            if subtitle.is_empty() {
+                String::new()
+            } else {
+                format!("<h2>{}</h2>\n", escape_html(subtitle))
+            },
+            self.contents_format
+                .to_html(localized(&article.contents, &self.lang)),
+            html_attachments(article, self.lang.clone())
+        )
+    }
+
+    // Case-insensitive substring match on the title and the text of the contents
+    fn search(&self, query: &str) -> String {
+        let mut body = search_form(query);
+        let needle = query.trim().to_lowercase();
+        if needle.is_empty() {
+            return body;
+        }
+
+        let mut matches = 0;
+        body.push_str("<ul>\n");
+
+        for id in &self.article_ids {
+            let article = &self.articles[id];
+            let title = localized(&article.titles, &self.title_lang);
+            let contents = self
+                .contents_format
+                .to_text(localized(&article.contents, &self.lang));
+
+            if !title.to_lowercase().contains(&needle) && !contents.to_lowercase().contains(&needle)
+            {
+                continue;
+            }
+
+            matches += 1;
+            body.push_str(&format!(
+                "<li><a href=\"/articles/{}\">{}</a><br>{}</li>\n",
+                article.id,
+                escape_html(title),
+                escape_html(&snippet(&contents, SEARCH_SNIPPET_LENGTH))
+            ));
+        }
+
+        body.push_str("</ul>\n");
+        body.push_str(&format!("<p>{} articles match</p>\n", matches));
+
+        body
+    }
+}
+
+fn search_query(request: &Request<Body>) -> Option<String> {
+    let url = reqwest::Url::parse(&format!("http://localhost{}", request.uri())).ok()?;
+
+    url.query_pairs()
+        .find(|(key, _)| key == "q")
+        .map(|(_, value)| value.into_owned())
+}
+
+fn search_form(query: &str) -> String {
+    format!(
+        "<form action=\"/search\"><input name=\"q\" value=\"{}\"> <button>Search</button></form>\n",
+        escape_html(query)
+    )
+}
+
+fn page(status: StatusCode, title: &str, body: &str) -> Response<Body> {
+    let html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>.content {{ white-space: pre-wrap; }}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        body
+    );
+
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "text/html; charset=utf-8")
+        .body(Body::from(html))
+        .unwrap()
+}
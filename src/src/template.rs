@@ -0,0 +1,96 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    export::sorted_attachments, localized, ArticleDataResponse, CategoryChildResponse, LangEnum,
+};
+
+// Template files looked up in --template-dir; any that is missing keeps the built-in rendering
+pub const ARTICLE_PAGE: &str = "article.html";
+pub const HTML_ENTRY: &str = "entry.html";
+pub const MD_ENTRY: &str = "entry.md";
+
+pub struct Templates {
+    tera: tera::Tera,
+    // Category id -> titles from the top-level category down to it
+    category_paths: HashMap<i32, Vec<String>>,
+}
+
+impl Templates {
+    pub fn load(
+        dir: &Path,
+        categories: &[CategoryChildResponse],
+        title_lang: LangEnum,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            dir.is_dir(),
+            "Template directory {} does not exist",
+            dir.display()
+        );
+
+        let files = [ARTICLE_PAGE, HTML_ENTRY, MD_ENTRY]
+            .into_iter()
+            .map(|name| (dir.join(name), Some(name)))
+            .filter(|(path, _)| path.exists())
+            .collect::<Vec<_>>();
+
+        // .html templates escape their variables, .md ones do not
+        let mut tera = tera::Tera::default();
+        tera.add_template_files(files)?;
+
+        let mut category_paths = HashMap::new();
+        collect_category_paths(categories, title_lang, &mut vec![], &mut category_paths);
+
+        Ok(Self {
+            tera,
+            category_paths,
+        })
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.tera.get_template_names().any(|loaded| loaded == name)
+    }
+
+    // Variables: id, title, subtitle, content, category_path (a list), image_url, url and
+    // attachments (a list ordered by position, then id)
+    pub fn render(
+        &self,
+        name: &str,
+        article: &ArticleDataResponse,
+        lang: LangEnum,
+        title_lang: LangEnum,
+        url: Option<&str>,
+    ) -> anyhow::Result<String> {
+        let category_path = match self.category_paths.get(&article.category_id) {
+            Some(path) => path.clone(),
+            // Without a category tree only the direct category is known
+            None => vec![localized(&article.category_titles, &title_lang).to_string()],
+        };
+
+        let mut context = tera::Context::new();
+        context.insert("id", &article.id);
+        context.insert("title", localized(&article.titles, &title_lang));
+        context.insert("subtitle", localized(&article.subtitles, &lang));
+        context.insert("content", localized(&article.contents, &lang));
+        context.insert("category_path", &category_path);
+        context.insert("image_url", &article.image_url);
+        context.insert("url", &url);
+        context.insert("attachments", &sorted_attachments(article, lang));
+
+        Ok(self.tera.render(name, &context)?)
+    }
+}
+
+fn collect_category_paths(
+    children: &[CategoryChildResponse],
+    title_lang: LangEnum,
+    path: &mut Vec<String>,
+    paths: &mut HashMap<i32, Vec<String>>,
+) {
+    for child in children.iter().filter(|child| child.type_ == "CATEGORY") {
+        path.push(localized(&child.titles, &title_lang).to_string());
+        paths.insert(child.id, path.clone());
+
+        collect_category_paths(&child.children, title_lang.clone(), path, paths);
+        path.pop();
+    }
+}
@@ -0,0 +1,64 @@
+use std::{path::Path, time::Duration};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::{crawl_site, Arguments};
+
+// Saving one article or crawling a batch touches many files in a row; wait for a quiet spell
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+pub async fn run(args: Arguments) -> anyhow::Result<()> {
+    crawl_site(args.clone()).await?;
+
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+
+        // Only the article JSONs themselves, not the .tmp files they are renamed from or the
+        // validator sidecars next to them
+        let relevant = !matches!(event.kind, EventKind::Access(_))
+            && event.paths.iter().any(|path| is_article_file(path));
+        if relevant {
+            sender.send(()).ok();
+        }
+    })?;
+
+    let articles_dir = args.output_dir.join("articles");
+    watcher.watch(&articles_dir, RecursiveMode::NonRecursive)?;
+
+    println!(
+        "Watching {} for changes, press Ctrl-C to stop",
+        articles_dir.display()
+    );
+
+    loop {
+        tokio::select! {
+            changed = receiver.recv() => {
+                if changed.is_none() {
+                    return Ok(());
+                }
+            }
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+
+        while let Ok(Some(())) = tokio::time::timeout(DEBOUNCE, receiver.recv()).await {}
+
+        println!("Cached articles changed, exporting again");
+
+        // A half-edited article should not end the session, the next save gets another try
+        if let Err(error) = crawl_site(args.clone()).await {
+            eprintln!("Export failed: {:#}", error);
+        }
+    }
+}
+
+fn is_article_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+        && path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.parse::<i32>().is_ok())
+}
@@ -0,0 +1,86 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{localized, Arguments, CrawlData, LangEnum};
+
+pub fn run(
+    args: &Arguments,
+    data: &CrawlData,
+    top: usize,
+    stopwords: Option<&Path>,
+) -> anyhow::Result<PathBuf> {
+    let stopwords = match stopwords {
+        Some(path) => load_stopwords(path)?,
+        None => HashSet::new(),
+    };
+
+    let mut counts = HashMap::<String, usize>::new();
+
+    for article in &data.ko_articles {
+        if !article.contents.contains_key(&args.lang) {
+            continue;
+        }
+
+        for token in tokenize(localized(&article.contents, &args.lang), args.lang.clone()) {
+            if !stopwords.contains(&token) {
+                *counts.entry(token).or_default() += 1;
+            }
+        }
+    }
+
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(top);
+
+    let final_dir = args.output_dir.join("final");
+    std::fs::create_dir_all(&final_dir).unwrap();
+
+    let file_path = final_dir.join(format!("wordfreq_{}.csv", args.lang).to_lowercase());
+    let mut writer = csv::Writer::from_path(&file_path)?;
+    writer.write_record(["token", "count"])?;
+    for (token, count) in &counts {
+        writer.serialize((token, count))?;
+    }
+    writer.flush()?;
+
+    Ok(file_path)
+}
+
+// One stopword per line, `#` starts a comment
+fn load_stopwords(path: &Path) -> anyhow::Result<HashSet<String>> {
+    let content = std::fs::read_to_string(path)?;
+
+    Ok(content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_lowercase())
+        .collect())
+}
+
+// English splits on words; Korean and Chinese use character bigrams, since neither a
+// whitespace split (KR particles) nor single characters (CN) give meaningful terms
+fn tokenize(text: &str, lang: LangEnum) -> Vec<String> {
+    let words = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase());
+
+    match lang {
+        LangEnum::EN => words.collect(),
+        _ => words
+            .flat_map(|word| {
+                let chars = word.chars().collect::<Vec<_>>();
+
+                // Latin words and numbers inside KR/CN text stay whole
+                if chars.len() < 2 || word.is_ascii() {
+                    return vec![word];
+                }
+
+                chars.windows(2).map(|pair| pair.iter().collect()).collect()
+            })
+            .collect(),
+    }
+}
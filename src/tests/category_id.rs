@@ -0,0 +1,42 @@
+mod common;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+#[tokio::test]
+async fn category_id_keeps_the_chosen_category_name() {
+    let output_dir = write_archive(
+        &[
+            category_node(
+                1,
+                "상위",
+                &[category_node(2, "하위", &[article_node(10, "첫째")])],
+            ),
+            category_node(3, "다른 분류", &[article_node(20, "둘째")]),
+        ],
+        &[
+            (10, article_json(10, "하위", "첫째", "본문 1")),
+            (20, article_json(20, "다른 분류", "둘째", "본문 2")),
+        ],
+    );
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--category-id".to_string(),
+        "1".to_string(),
+    ]);
+
+    dfu_crawler::run(args).await.unwrap();
+
+    let final_dir = output_dir.path().join("final");
+
+    let articles = std::fs::read_to_string(final_dir.join("all_articles.md")).unwrap();
+    assert_eq!(articles, "```[첫째]```\\\n본문 1\n\n\n\n");
+
+    let category_names = std::fs::read_to_string(final_dir.join("category_names.txt")).unwrap();
+    assert_eq!(category_names, "상위");
+}
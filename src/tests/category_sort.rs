@@ -0,0 +1,44 @@
+mod common;
+
+use std::collections::HashSet;
+
+use clap::Parser;
+use common::{article_json, article_node, at_position, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+async fn category_names(sort: &str) -> String {
+    let output_dir = write_archive(
+        &[
+            at_position(category_node(3, "나", &[article_node(30, "셋째")]), 0),
+            at_position(category_node(1, "다", &[article_node(10, "첫째")]), 1),
+            at_position(category_node(2, "가", &[article_node(20, "둘째")]), 2),
+        ],
+        &[
+            (10, article_json(10, "다", "첫째", "본문 1")),
+            (20, article_json(20, "가", "둘째", "본문 2")),
+            (30, article_json(30, "나", "셋째", "본문 3")),
+        ],
+    );
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--category-sort".to_string(),
+        sort.to_string(),
+    ]);
+    let data = dfu_crawler::read_from_local(&args).await.unwrap();
+    dfu_crawler::post_process(&args, &data, &[], &HashSet::new())
+        .await
+        .unwrap();
+
+    std::fs::read_to_string(output_dir.path().join("final/category_names.txt")).unwrap()
+}
+
+#[tokio::test]
+async fn category_names_follow_the_requested_order() {
+    assert_eq!(category_names("position").await, "나\n다\n가");
+    assert_eq!(category_names("title").await, "가\n나\n다");
+    assert_eq!(category_names("id").await, "다\n가\n나");
+}
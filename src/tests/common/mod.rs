@@ -0,0 +1,104 @@
+#![allow(dead_code)]
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    thread,
+};
+
+use tempfile::TempDir;
+
+pub fn article_json(id: i32, category: &str, title: &str, contents: &str) -> String {
+    format!(
+        r#"{{"code": "0", "message": "ok", "data": {{"id": {id}, "category_id": 1, "category_titles": {{"KR": "{category}"}}, "status": "PUBLISHED", "titles": {{"KR": "{title}"}}, "subtitles": {{"KR": ""}}, "image_url": null, "attachments": {{}}, "contents": {{"KR": "{contents}"}}}}}}"#
+    )
+}
+
+pub fn article_node(id: i32, title: &str) -> String {
+    format!(
+        r#"{{"id": {id}, "parent_id": null, "position": 0, "type": "ARTICLE", "status": "PUBLISHED", "titles": {{"KR": "{title}"}}, "children": [], "modified": false}}"#
+    )
+}
+
+pub fn category_node(id: i32, title: &str, children: &[String]) -> String {
+    format!(
+        r#"{{"id": {id}, "parent_id": null, "position": 0, "type": "CATEGORY", "status": "PUBLISHED", "titles": {{"KR": "{title}"}}, "children": [{}], "modified": false}}"#,
+        children.join(", ")
+    )
+}
+
+// The node helpers all sit at position 0
+pub fn at_position(node: String, position: i32) -> String {
+    node.replacen(
+        r#""position": 0"#,
+        &format!(r#""position": {}"#, position),
+        1,
+    )
+}
+
+// A fresh directory per test, so tests running in parallel never share one; removed on drop
+pub fn output_dir() -> TempDir {
+    tempfile::Builder::new()
+        .prefix("dfu_crawler_test")
+        .tempdir()
+        .unwrap()
+}
+
+// Lays out a local archive the way a finished crawl leaves it
+pub fn write_archive(categories: &[String], articles: &[(i32, String)]) -> TempDir {
+    let output_dir = self::output_dir();
+    let path = output_dir.path();
+
+    std::fs::create_dir_all(path.join("category")).unwrap();
+    std::fs::write(
+        path.join("category").join("categories.json"),
+        format!(
+            r#"{{"code": "0", "message": "ok", "data": [{}]}}"#,
+            categories.join(", ")
+        ),
+    )
+    .unwrap();
+
+    std::fs::create_dir_all(path.join("articles")).unwrap();
+    for (id, json) in articles {
+        std::fs::write(path.join("articles").join(format!("{}.json", id)), json).unwrap();
+    }
+
+    output_dir
+}
+
+// Plain HTTP on a free port, `respond` maps each request line to a status and JSON body;
+// returns the base URL
+pub fn serve(respond: impl Fn(&str) -> (&'static str, String) + Send + 'static) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header == "\r\n" || header.is_empty() {
+                    break;
+                }
+            }
+
+            let (status, body) = respond(&request_line);
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+
+    base_url
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+#[tokio::test]
+async fn incremental_crawl_reads_and_folds_the_completed_log() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let server_requests = requests.clone();
+    let base_url = common::serve(move |request_line| {
+        server_requests
+            .lock()
+            .unwrap()
+            .push(request_line.to_string());
+
+        if request_line.contains("categories.json") {
+            let categories = category_node(
+                1,
+                "분류",
+                &[
+                    article_node(101, "첫째"),
+                    article_node(102, "둘째"),
+                    article_node(103, "셋째"),
+                ],
+            );
+            let body = format!(
+                r#"{{"code": "0", "message": "ok", "data": [{}]}}"#,
+                categories
+            );
+            ("200 OK", body)
+        } else {
+            ("200 OK", article_json(103, "분류", "셋째", "본문 3"))
+        }
+    });
+
+    // 101 made it into completed.json, 102 only into the log, where the crawl was cut short
+    // halfway through writing 103
+    let output_dir = write_archive(
+        &[],
+        &[
+            (101, article_json(101, "분류", "첫째", "본문 1")),
+            (102, article_json(102, "분류", "둘째", "본문 2")),
+        ],
+    );
+    std::fs::remove_dir_all(output_dir.path().join("category")).unwrap();
+    std::fs::write(output_dir.path().join("completed.json"), "[101]").unwrap();
+    std::fs::write(output_dir.path().join("completed.jsonl"), "102\n10").unwrap();
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "--rps".to_string(),
+        "100".to_string(),
+        "--incremental".to_string(),
+        "-y".to_string(),
+    ]);
+    dfu_crawler::run(args).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    let article_requests = requests
+        .iter()
+        .filter(|request| request.contains("/story/"))
+        .collect::<Vec<_>>();
+    assert_eq!(article_requests.len(), 1);
+    assert!(article_requests[0].contains("/story/103"));
+
+    let completed = std::fs::read_to_string(output_dir.path().join("completed.json")).unwrap();
+    assert_eq!(completed, "[101,102,103]");
+    assert!(!output_dir.path().join("completed.jsonl").exists());
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    thread,
+};
+
+use clap::Parser;
+use dfu_crawler::Arguments;
+use flate2::{write::GzEncoder, Compression};
+
+const CATEGORIES: &str = r#"{"code": "0", "message": "ok", "data": [{"id": 1, "parent_id": null, "position": 0, "type": "CATEGORY", "status": "PUBLISHED", "titles": {"KR": "분류"}, "children": [{"id": 101, "parent_id": 1, "position": 0, "type": "ARTICLE", "status": "PUBLISHED", "titles": {"KR": "제목"}, "children": [], "modified": false}], "modified": false}]}"#;
+
+const ARTICLE: &str = r#"{"code": "0", "message": "ok", "data": {"id": 101, "category_id": 1, "category_titles": {"KR": "분류"}, "status": "PUBLISHED", "titles": {"KR": "제목"}, "subtitles": {"KR": ""}, "image_url": null, "attachments": {}, "contents": {"KR": "압축된 본문"}}}"#;
+
+// Serves every request with a gzip-encoded body, whatever the client asked for
+fn serve_gzip(listener: TcpListener) {
+    for stream in listener.incoming() {
+        let mut stream = stream.unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+
+        let mut accepts_gzip = false;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+
+            if header.to_lowercase().starts_with("accept-encoding:") && header.contains("gzip") {
+                accepts_gzip = true;
+            }
+            if header == "\r\n" || header.is_empty() {
+                break;
+            }
+        }
+        assert!(accepts_gzip, "client did not negotiate gzip");
+
+        let body = if request_line.contains("categories.json") {
+            CATEGORIES
+        } else {
+            ARTICLE
+        };
+
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            compressed.len()
+        )
+        .unwrap();
+        stream.write_all(&compressed).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn decodes_gzip_responses() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || serve_gzip(listener));
+
+    let output_dir = common::output_dir();
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "--rps".to_string(),
+        "100".to_string(),
+        "--verify-downloads".to_string(),
+    ]);
+
+    dfu_crawler::run(args).await.unwrap();
+
+    let cached =
+        std::fs::read_to_string(output_dir.path().join("articles").join("101.json")).unwrap();
+    assert_eq!(cached, ARTICLE);
+
+    let articles =
+        std::fs::read_to_string(output_dir.path().join("final").join("all_articles.md")).unwrap();
+    assert_eq!(articles, "```[제목]```\\\n압축된 본문\n\n\n\n");
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node};
+use dfu_crawler::Arguments;
+
+#[test]
+fn zero_concurrent_writes_are_rejected() {
+    let error = Arguments::try_parse_from(["dfu_crawler", "--max-concurrent-writes", "0"])
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("0 is not in 1.."), "{}", error);
+}
+
+#[tokio::test]
+async fn a_single_write_permit_still_writes_every_download() {
+    let base_url = common::serve(|request_line| {
+        if request_line.contains("categories.json") {
+            let categories = category_node(
+                1,
+                "분류",
+                &[
+                    article_node(101, "첫째"),
+                    article_node(102, "둘째"),
+                    article_node(103, "셋째"),
+                ],
+            );
+            let body = format!(
+                r#"{{"code": "0", "message": "ok", "data": [{}]}}"#,
+                categories
+            );
+            ("200 OK", body)
+        } else if request_line.contains("/story/101") {
+            ("200 OK", article_json(101, "분류", "첫째", "본문 1"))
+        } else if request_line.contains("/story/102") {
+            ("200 OK", article_json(102, "분류", "둘째", "본문 2"))
+        } else {
+            ("200 OK", article_json(103, "분류", "셋째", "본문 3"))
+        }
+    });
+
+    let output_dir = common::output_dir();
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "--rps".to_string(),
+        "100".to_string(),
+        "--max-concurrent-writes".to_string(),
+        "1".to_string(),
+        "-y".to_string(),
+    ]);
+    dfu_crawler::run(args).await.unwrap();
+
+    for id in [101, 102, 103] {
+        let cached = output_dir
+            .path()
+            .join("articles")
+            .join(format!("{}.json", id));
+        assert!(cached.exists(), "{}", cached.display());
+    }
+}
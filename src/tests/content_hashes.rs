@@ -0,0 +1,86 @@
+mod common;
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use clap::Parser;
+use common::{article_json, article_node, category_node};
+use dfu_crawler::Arguments;
+
+fn args(output_dir: &std::path::Path, base_url: &str) -> Arguments {
+    Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "--rps".to_string(),
+        "100".to_string(),
+        "--incremental".to_string(),
+        "-y".to_string(),
+    ])
+}
+
+#[tokio::test]
+async fn incremental_crawl_keeps_the_cached_copy_of_unchanged_articles() {
+    let edited = Arc::new(AtomicBool::new(false));
+    let server_edited = edited.clone();
+    let base_url = common::serve(move |request_line| {
+        if request_line.contains("categories.json") {
+            let categories = category_node(
+                1,
+                "분류",
+                &[article_node(101, "첫째"), article_node(102, "둘째")],
+            );
+            let body = format!(
+                r#"{{"code": "0", "message": "ok", "data": [{}]}}"#,
+                categories
+            );
+            ("200 OK", body)
+        } else if request_line.contains("/story/101") {
+            ("200 OK", article_json(101, "분류", "첫째", "본문 1"))
+        } else if server_edited.load(Ordering::SeqCst) {
+            ("200 OK", article_json(102, "분류", "둘째", "고친 본문 2"))
+        } else {
+            ("200 OK", article_json(102, "분류", "둘째", "본문 2"))
+        }
+    });
+
+    let output_dir = common::output_dir();
+    dfu_crawler::run(args(output_dir.path(), &base_url))
+        .await
+        .unwrap();
+
+    let index =
+        std::fs::read_to_string(output_dir.path().join("final/articles_index.json")).unwrap();
+    assert!(index.contains(r#""content_hash": ""#));
+
+    // Marks both cached copies without touching their contents, then has 102 change on the site;
+    // dropping completed.json makes the next run fetch both again
+    let articles_dir = output_dir.path().join("articles");
+    std::fs::write(
+        articles_dir.join("101.json"),
+        article_json(101, "분류", "표시", "본문 1"),
+    )
+    .unwrap();
+    std::fs::write(
+        articles_dir.join("102.json"),
+        article_json(102, "분류", "표시", "본문 2"),
+    )
+    .unwrap();
+    std::fs::remove_file(output_dir.path().join("completed.json")).unwrap();
+    edited.store(true, Ordering::SeqCst);
+
+    dfu_crawler::run(args(output_dir.path(), &base_url))
+        .await
+        .unwrap();
+
+    let unchanged = std::fs::read_to_string(articles_dir.join("101.json")).unwrap();
+    assert!(unchanged.contains("표시"));
+    let changed = std::fs::read_to_string(articles_dir.join("102.json")).unwrap();
+    assert!(changed.contains("고친 본문 2"));
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use std::collections::HashSet;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+#[tokio::test]
+async fn excluded_categories_are_dropped_from_articles_and_names() {
+    let output_dir = write_archive(
+        &[
+            category_node(1, "제외", &[article_node(10, "빠질 글")]),
+            category_node(2, "유지", &[article_node(20, "남을 글")]),
+            // Stray (non-breaking) spaces must not let a category slip past the filter
+            category_node(3, "\u{a0}공백  제외 ", &[article_node(30, "공백 글")]),
+        ],
+        &[
+            (10, article_json(10, "제외", "빠질 글", "본문 1")),
+            (20, article_json(20, "유지", "남을 글", "본문 2")),
+            (
+                30,
+                article_json(30, "\u{a0}공백  제외 ", "공백 글", "본문 3"),
+            ),
+        ],
+    );
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+    ]);
+    let data = dfu_crawler::read_from_local(&args).await.unwrap();
+
+    let exclude_categories = ["제외".to_string(), "공백 제외".to_string()];
+    let summary = dfu_crawler::post_process(&args, &data, &exclude_categories, &HashSet::new())
+        .await
+        .unwrap();
+
+    assert_eq!(summary.articles_written, 1);
+    assert_eq!(summary.excluded, 2);
+
+    let final_dir = output_dir.path().join("final");
+
+    let articles = std::fs::read_to_string(final_dir.join("all_articles.md")).unwrap();
+    assert_eq!(articles, "```[남을 글]```\\\n본문 2\n\n\n\n");
+
+    let category_names = std::fs::read_to_string(final_dir.join("category_names.txt")).unwrap();
+    assert_eq!(category_names, "유지");
+
+    // The index lists what was written, not everything that was read
+    let index: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(final_dir.join("articles_index.json")).unwrap(),
+    )
+    .unwrap();
+    let ids = index
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["id"].as_i64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [20]);
+}
+
+#[tokio::test]
+async fn ignored_ids_are_excluded_without_touching_category_names() {
+    let output_dir = write_archive(
+        &[category_node(
+            1,
+            "유지",
+            &[article_node(10, "첫째"), article_node(20, "둘째")],
+        )],
+        &[
+            (10, article_json(10, "유지", "첫째", "본문 1")),
+            (20, article_json(20, "유지", "둘째", "본문 2")),
+        ],
+    );
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+    ]);
+    let data = dfu_crawler::read_from_local(&args).await.unwrap();
+
+    let summary = dfu_crawler::post_process(&args, &data, &[], &HashSet::from([10]))
+        .await
+        .unwrap();
+
+    assert_eq!(summary.articles_written, 1);
+    assert_eq!(summary.excluded, 1);
+
+    let final_dir = output_dir.path().join("final");
+
+    let articles = std::fs::read_to_string(final_dir.join("all_articles.md")).unwrap();
+    assert_eq!(articles, "```[둘째]```\\\n본문 2\n\n\n\n");
+
+    let category_names = std::fs::read_to_string(final_dir.join("category_names.txt")).unwrap();
+    assert_eq!(category_names, "유지");
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use std::path::Path;
+
+use clap::Parser;
+use common::{article_json, article_node, at_position, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+async fn export(output_dir: &Path, extra_args: &[&str]) -> String {
+    let mut args = vec![
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.display().to_string(),
+    ];
+    args.extend(extra_args.iter().map(|arg| arg.to_string()));
+
+    dfu_crawler::run(Arguments::parse_from(args)).await.unwrap();
+
+    std::fs::read_to_string(output_dir.join("final").join("all_articles.md")).unwrap()
+}
+
+#[tokio::test]
+async fn flat_export_is_unchanged() {
+    let output_dir = write_archive(
+        &[category_node(1, "분류", &[article_node(10, "제목")])],
+        &[(10, article_json(10, "분류", "제목", "본문\\n둘째 줄"))],
+    );
+
+    let articles = export(output_dir.path(), &[]).await;
+    assert_eq!(articles, "```[제목]```\\\n본문\n둘째 줄\n\n\n\n");
+}
+
+#[tokio::test]
+async fn hierarchical_export_follows_the_tree() {
+    let output_dir = write_archive(
+        &[
+            category_node(
+                1,
+                "상위",
+                &[
+                    article_node(10, "첫째"),
+                    category_node(2, "하위", &[article_node(20, "둘째")]),
+                ],
+            ),
+            category_node(3, "빈 분류", &[]),
+        ],
+        &[
+            (20, article_json(20, "하위", "둘째", "본문 2")),
+            (10, article_json(10, "상위", "첫째", "본문 1")),
+        ],
+    );
+
+    // Both siblings sit at position 0, so the subcategory's lower id puts it first
+    let articles = export(output_dir.path(), &["--hierarchical"]).await;
+    assert_eq!(
+        articles,
+        "# 상위\n\n## 하위\n\n```[둘째]```\\\n본문 2\n\n\n\n\n```[첫째]```\\\n본문 1\n\n\n\n"
+    );
+}
+
+#[tokio::test]
+async fn siblings_follow_their_position_then_their_id() {
+    // Listed out of order, with 30 and 20 sharing a position
+    let output_dir = write_archive(
+        &[category_node(
+            1,
+            "분류",
+            &[
+                at_position(article_node(30, "셋째"), 1),
+                at_position(article_node(10, "첫째"), 2),
+                at_position(article_node(20, "둘째"), 1),
+            ],
+        )],
+        &[
+            (10, article_json(10, "분류", "첫째", "본문 1")),
+            (20, article_json(20, "분류", "둘째", "본문 2")),
+            (30, article_json(30, "분류", "셋째", "본문 3")),
+        ],
+    );
+
+    let articles = export(output_dir.path(), &["--hierarchical"]).await;
+    let titles = articles
+        .lines()
+        .filter(|line| line.starts_with("```["))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        titles,
+        ["```[둘째]```\\", "```[셋째]```\\", "```[첫째]```\\"]
+    );
+}
+
+#[tokio::test]
+async fn export_langs_write_every_format_in_every_language() {
+    let output_dir = write_archive(
+        &[category_node(1, "분류", &[article_node(10, "제목")])],
+        &[(
+            10,
+            article_json(10, "분류", "제목", "본문").replace(
+                r#""contents": {"KR": "본문"}"#,
+                r#""contents": {"KR": "본문", "EN": "Body"}"#,
+            ),
+        )],
+    );
+
+    let args = Arguments::parse_from([
+        "dfu_crawler",
+        "--use-local",
+        "--output-dir",
+        &output_dir.path().display().to_string(),
+        "--output-format",
+        "md,json",
+        "--export-langs",
+        "kr,en",
+    ]);
+    dfu_crawler::run(args).await.unwrap();
+
+    let final_dir = output_dir.path().join("final");
+    for (file_name, contents) in [
+        ("all_articles.kr.md", "본문"),
+        ("all_articles.en.md", "Body"),
+        ("all_articles.kr.json", "본문"),
+        ("all_articles.en.json", "Body"),
+    ] {
+        let exported = std::fs::read_to_string(final_dir.join(file_name)).unwrap();
+        assert!(exported.contains(contents), "{}: {}", file_name, exported);
+    }
+    assert!(!final_dir.join("all_articles.md").exists());
+}
+
+#[tokio::test]
+async fn flattened_json_has_one_record_per_line() {
+    let output_dir = write_archive(
+        &[category_node(
+            1,
+            "분류",
+            &[article_node(10, "첫째"), article_node(20, "둘째")],
+        )],
+        &[
+            (10, article_json(10, "분류", "첫째", "본문\\n둘째 줄")),
+            (20, article_json(20, "분류", "둘째", "한 줄")),
+        ],
+    );
+
+    // A single format keeps the --articles-file name
+    let json = export(
+        output_dir.path(),
+        &["--output-format", "json", "--flatten-contents-newlines"],
+    )
+    .await;
+    let lines = json.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 4, "{}", json);
+    assert_eq!((lines[0], lines[3]), ("[", "]"));
+
+    let first: serde_json::Value = serde_json::from_str(lines[1].trim_end_matches(',')).unwrap();
+    assert_eq!(first["contents"]["KR"], "본문 둘째 줄");
+    let second: serde_json::Value = serde_json::from_str(lines[2]).unwrap();
+    assert_eq!(second["id"], 20);
+}
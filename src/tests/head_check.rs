@@ -0,0 +1,108 @@
+mod common;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node};
+use dfu_crawler::Arguments;
+
+// 101 is the lowest id in the tree but never loads, 102 does
+fn serve(categories: String) -> String {
+    common::serve(move |request_line| {
+        if request_line.contains("categories.json") {
+            let body = format!(
+                r#"{{"code": "0", "message": "ok", "data": [{}]}}"#,
+                categories
+            );
+            ("200 OK", body)
+        } else if request_line.contains("/story/102") {
+            ("200 OK", article_json(102, "둘째 분류", "둘째", "본문 2"))
+        } else {
+            ("404 Not Found", String::new())
+        }
+    })
+}
+
+fn args(base_url: &str, extra: &[&str]) -> (tempfile::TempDir, Arguments) {
+    let output_dir = common::output_dir();
+    let args = Arguments::parse_from(
+        [
+            "dfu_crawler".to_string(),
+            "--output-dir".to_string(),
+            output_dir.path().display().to_string(),
+            "--categories-url".to_string(),
+            format!("{}/categories.json", base_url),
+            "--article-base-url".to_string(),
+            format!("{}/story", base_url),
+            "--rps".to_string(),
+            "100".to_string(),
+            "--head-check".to_string(),
+            "-y".to_string(),
+        ]
+        .into_iter()
+        .chain(extra.iter().map(|arg| arg.to_string())),
+    );
+
+    (output_dir, args)
+}
+
+#[tokio::test]
+async fn the_sample_comes_from_the_selected_category() {
+    let base_url = serve(
+        [
+            category_node(1, "첫째 분류", &[article_node(101, "첫째")]),
+            category_node(2, "둘째 분류", &[article_node(102, "둘째")]),
+        ]
+        .join(", "),
+    );
+
+    let (_output_dir, args) = args(&base_url, &["--category-id", "2"]);
+    dfu_crawler::run(args).await.unwrap();
+}
+
+#[tokio::test]
+async fn the_sample_skips_excluded_statuses() {
+    let draft = article_node(101, "첫째").replacen("PUBLISHED", "DRAFT", 1);
+    let base_url = serve(category_node(
+        2,
+        "둘째 분류",
+        &[draft, article_node(102, "둘째")],
+    ));
+
+    let (_output_dir, args) = args(&base_url, &["--exclude-status", "draft", "--graceful-404"]);
+    dfu_crawler::run(args).await.unwrap();
+}
+
+#[tokio::test]
+async fn the_preflight_still_fails_on_an_article_that_would_be_crawled() {
+    let base_url = serve(category_node(
+        2,
+        "둘째 분류",
+        &[article_node(101, "첫째"), article_node(102, "둘째")],
+    ));
+
+    let (_output_dir, args) = args(&base_url, &[]);
+    let error = dfu_crawler::run(args).await.unwrap_err().to_string();
+    assert!(
+        error.contains("Preflight failed, sample article 101"),
+        "{}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn the_sample_stays_within_max_depth() {
+    // 101 sits a level deeper than --max-depth 1 lets the crawl go
+    let base_url = serve(
+        [
+            category_node(
+                1,
+                "첫째 분류",
+                &[category_node(3, "하위", &[article_node(101, "첫째")])],
+            ),
+            category_node(2, "둘째 분류", &[article_node(102, "둘째")]),
+        ]
+        .join(", "),
+    );
+
+    let (_output_dir, args) = args(&base_url, &["--max-depth", "1"]);
+    dfu_crawler::run(args).await.unwrap();
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use clap::Parser;
+use common::article_json;
+use dfu_crawler::Arguments;
+
+fn parse(range: &str) -> Result<Arguments, String> {
+    // Joined with =, so a negative id is not taken for a flag
+    Arguments::try_parse_from(["dfu_crawler", &format!("--article-id-range={}", range)])
+        .map_err(|e| e.to_string())
+}
+
+#[test]
+fn ranges_with_ids_in_them_are_accepted() {
+    for range in ["100..500", "5..6", "5..=5", "-2147483648..-2147483647"] {
+        assert!(parse(range).is_ok(), "{}", range);
+    }
+}
+
+#[test]
+fn empty_ranges_are_rejected() {
+    for range in [
+        "10..5",
+        "5..5",
+        "6..=5",
+        "0..-2147483648",
+        "-2147483648..-2147483648",
+    ] {
+        let error = parse(range).unwrap_err();
+        assert!(
+            error.contains("is empty, START has to be below END"),
+            "{}: {}",
+            range,
+            error
+        );
+    }
+}
+
+#[test]
+fn malformed_ranges_are_rejected() {
+    assert!(parse("100-500")
+        .unwrap_err()
+        .contains("expected START..END, got 100-500"));
+    assert!(parse("a..5").unwrap_err().contains("invalid digit"));
+}
+
+#[tokio::test]
+async fn articles_disallowed_by_robots_are_skipped_rather_than_failed() {
+    let base_url = common::serve(|request_line| {
+        if request_line.contains("robots.txt") {
+            (
+                "200 OK",
+                "User-agent: *\nDisallow: /story/102\n".to_string(),
+            )
+        } else if request_line.contains("/story/101") {
+            ("200 OK", article_json(101, "분류", "첫째", "본문 1"))
+        } else {
+            ("200 OK", article_json(102, "분류", "둘째", "본문 2"))
+        }
+    });
+
+    let output_dir = common::output_dir();
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "--rps".to_string(),
+        "100".to_string(),
+        "--article-id-range=101..=102".to_string(),
+        "--respect-robots".to_string(),
+        "--fail-fast".to_string(),
+        "-y".to_string(),
+    ]);
+    dfu_crawler::run(args).await.unwrap();
+
+    let articles_dir = output_dir.path().join("articles");
+    assert!(articles_dir.join("101.json").exists());
+    assert!(!articles_dir.join("102.json").exists());
+}
+
+#[tokio::test]
+async fn articles_without_a_korean_category_are_recorded_as_failed() {
+    let base_url = common::serve(|request_line| {
+        if request_line.contains("/story/101") {
+            let english_only = article_json(101, "Category", "First", "Body 1").replacen(
+                r#""category_titles": {"KR""#,
+                r#""category_titles": {"EN""#,
+                1,
+            );
+            ("200 OK", english_only)
+        } else {
+            ("200 OK", article_json(102, "분류", "둘째", "본문 2"))
+        }
+    });
+
+    let output_dir = common::output_dir();
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "--rps".to_string(),
+        "100".to_string(),
+        "--article-id-range=101..=102".to_string(),
+        "-y".to_string(),
+    ]);
+    // The rest of the span is still exported before the run reports the failure
+    let error = dfu_crawler::run(args).await.unwrap_err().to_string();
+    assert_eq!(error, "1 articles failed to download");
+
+    let failed = std::fs::read_to_string(output_dir.path().join("failed.json")).unwrap();
+    assert_eq!(failed, "[101]");
+    let category_names =
+        std::fs::read_to_string(output_dir.path().join("final/category_names.txt")).unwrap();
+    assert_eq!(category_names, "분류");
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    thread,
+};
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+// Whitespace and a BOM ahead of the JSON, the BOM after the padding as a CDN leaves it
+fn padded(json: &str) -> String {
+    format!("\r\n  \u{feff}\n{}\n\n", json)
+}
+
+fn categories() -> String {
+    format!(
+        "{{\"code\": \"0\", \"message\": \"ok\", \"data\": [{}]}}",
+        category_node(1, "분류", &[article_node(101, "제목")])
+    )
+}
+
+// Plain HTTP: the padded category listing for categories.json, the article for anything else
+fn serve(listener: TcpListener) {
+    for stream in listener.incoming() {
+        let mut stream = stream.unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            if header == "\r\n" || header.is_empty() {
+                break;
+            }
+        }
+
+        let body = if request_line.contains("categories.json") {
+            padded(&categories())
+        } else {
+            article_json(101, "분류", "제목", "본문")
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn padded_categories_from_the_web_are_read() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || serve(listener));
+
+    let output_dir = common::output_dir();
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "--rps".to_string(),
+        "100".to_string(),
+    ]);
+
+    dfu_crawler::run(args).await.unwrap();
+
+    let exported =
+        std::fs::read_to_string(output_dir.path().join("final").join("all_articles.md")).unwrap();
+    assert_eq!(exported, "```[제목]```\\\n본문\n\n\n\n");
+}
+
+#[tokio::test]
+async fn local_json_with_a_bom_or_padding_is_read() {
+    // A bare BOM on the article, BOM and padding on the category listing
+    let output_dir = write_archive(
+        &[],
+        &[(
+            101,
+            format!("\u{feff}{}", article_json(101, "분류", "제목", "본문")),
+        )],
+    );
+    std::fs::write(
+        output_dir.path().join("category").join("categories.json"),
+        padded(&categories()),
+    )
+    .unwrap();
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+    ]);
+
+    dfu_crawler::run(args).await.unwrap();
+
+    let exported =
+        std::fs::read_to_string(output_dir.path().join("final").join("all_articles.md")).unwrap();
+    assert_eq!(exported, "```[제목]```\\\n본문\n\n\n\n");
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    path::Path,
+    thread,
+};
+
+use clap::Parser;
+use common::{article_json, article_node, category_node};
+use dfu_crawler::Arguments;
+
+fn categories() -> String {
+    format!(
+        "{{\"code\": \"0\", \"message\": \"ok\", \"data\": [{}]}}",
+        category_node(
+            1,
+            "분류",
+            &[article_node(101, "제목"), article_node(102, "없음")]
+        )
+    )
+}
+
+// Plain HTTP: nothing under /broken or for article 102, the listing and article 101 otherwise
+fn serve(listener: TcpListener) {
+    for stream in listener.incoming() {
+        let mut stream = stream.unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            if header == "\r\n" || header.is_empty() {
+                break;
+            }
+        }
+
+        let (status, body) = if request_line.contains("/broken/") || request_line.contains("102") {
+            ("404 Not Found", String::new())
+        } else if request_line.contains("categories.json") {
+            ("200 OK", categories())
+        } else {
+            ("200 OK", article_json(101, "분류", "제목", "본문"))
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+        .unwrap();
+    }
+}
+
+fn start_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || serve(listener));
+    base_url
+}
+
+fn arguments(output_dir: &Path, extra_args: &[String]) -> Arguments {
+    let mut args = vec![
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.display().to_string(),
+        "--rps".to_string(),
+        "100".to_string(),
+        "--retries".to_string(),
+        "0".to_string(),
+        "-y".to_string(),
+    ];
+    args.extend(extra_args.iter().cloned());
+
+    Arguments::parse_from(args)
+}
+
+fn write_sources(output_dir: &Path, base_url: &str) -> String {
+    let sources = output_dir.join("sources.json");
+    std::fs::write(
+        &sources,
+        format!(
+            r#"[
+                {{"name": "broken", "categories_url": "{0}/broken/categories.json", "article_base_url": "{0}/broken/story"}},
+                {{"name": "good", "categories_url": "{0}/categories.json", "article_base_url": "{0}/story"}}
+            ]"#,
+            base_url
+        ),
+    )
+    .unwrap();
+
+    sources.display().to_string()
+}
+
+#[tokio::test]
+async fn failed_articles_are_reported_after_the_export() {
+    let base_url = start_server();
+    let output_dir = common::output_dir();
+
+    let args = arguments(
+        output_dir.path(),
+        &[
+            "--categories-url".to_string(),
+            format!("{}/categories.json", base_url),
+            "--article-base-url".to_string(),
+            format!("{}/story", base_url),
+        ],
+    );
+    let error = dfu_crawler::run(args).await.unwrap_err();
+    assert_eq!(error.to_string(), "1 articles failed to download");
+
+    let exported =
+        std::fs::read_to_string(output_dir.path().join("final").join("all_articles.md")).unwrap();
+    assert_eq!(exported, "```[제목]```\\\n본문\n\n\n\n");
+}
+
+#[tokio::test]
+async fn a_site_that_fails_to_load_does_not_stop_the_others() {
+    let base_url = start_server();
+    let output_dir = common::output_dir();
+    let sources = write_sources(output_dir.path(), &base_url);
+
+    let args = arguments(output_dir.path(), &["--sources".to_string(), sources]);
+    let error = dfu_crawler::run(args).await.unwrap_err();
+    assert_eq!(error.to_string(), "2 sites failed to crawl");
+
+    // The good site still exported what it could fetch
+    let exported = std::fs::read_to_string(
+        output_dir
+            .path()
+            .join("good")
+            .join("final")
+            .join("all_articles.md"),
+    )
+    .unwrap();
+    assert_eq!(exported, "```[제목]```\\\n본문\n\n\n\n");
+}
+
+#[tokio::test]
+async fn fail_fast_stops_at_the_first_failed_site() {
+    let base_url = start_server();
+    let output_dir = common::output_dir();
+    let sources = write_sources(output_dir.path(), &base_url);
+
+    let args = arguments(
+        output_dir.path(),
+        &["--sources".to_string(), sources, "--fail-fast".to_string()],
+    );
+    dfu_crawler::run(args).await.unwrap_err();
+
+    assert!(!output_dir.path().join("good").exists());
+}
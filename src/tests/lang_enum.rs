@@ -0,0 +1,49 @@
+mod common;
+
+use std::collections::HashMap;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::{Arguments, LangEnum};
+
+#[test]
+fn unknown_languages_keep_their_code_through_a_round_trip() {
+    let json = r#"{"CN":"中文","JP":"日本語"}"#;
+
+    let texts: HashMap<LangEnum, String> = serde_json::from_str(json).unwrap();
+    assert_eq!(texts[&LangEnum::CN], "中文");
+    assert_eq!(texts[&LangEnum::Other("JP".to_string())], "日本語");
+
+    let serialized = serde_json::to_string(&LangEnum::Other("JP".to_string())).unwrap();
+    assert_eq!(serialized, r#""JP""#);
+
+    let round_trip: HashMap<LangEnum, String> =
+        serde_json::from_str(&serde_json::to_string(&texts).unwrap()).unwrap();
+    assert_eq!(round_trip, texts);
+}
+
+#[tokio::test]
+async fn articles_with_unknown_languages_still_export() {
+    let output_dir = write_archive(
+        &[category_node(1, "분류", &[article_node(10, "제목")])],
+        &[(
+            10,
+            article_json(10, "분류", "제목", "본문").replace(
+                r#""contents": {"KR": "본문"}"#,
+                r#""contents": {"KR": "본문", "JP": "本文"}"#,
+            ),
+        )],
+    );
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+    ]);
+    dfu_crawler::run(args).await.unwrap();
+
+    let exported =
+        std::fs::read_to_string(output_dir.path().join("final").join("all_articles.md")).unwrap();
+    assert_eq!(exported, "```[제목]```\\\n본문\n\n\n\n");
+}
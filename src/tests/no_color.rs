@@ -0,0 +1,29 @@
+mod common;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+#[cfg(unix)]
+#[tokio::test]
+async fn no_color_reaches_the_content_transform() {
+    let output_dir = write_archive(
+        &[category_node(1, "분류", &[article_node(10, "제목")])],
+        &[(10, article_json(10, "분류", "제목", "본문"))],
+    );
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--content-transform".to_string(),
+        "printf %s \"${NO_COLOR:-unset}\"".to_string(),
+        "--no-color".to_string(),
+    ]);
+    dfu_crawler::run(args).await.unwrap();
+
+    let exported =
+        std::fs::read_to_string(output_dir.path().join("final").join("all_articles.md")).unwrap();
+    assert_eq!(exported, "```[제목]```\\\n1\n\n\n\n");
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use std::path::Path;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+async fn export(output_dir: &Path, encoding: &str) -> Vec<u8> {
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.display().to_string(),
+        "--output-encoding".to_string(),
+        encoding.to_string(),
+    ]);
+
+    dfu_crawler::run(args).await.unwrap();
+
+    std::fs::read(output_dir.join("final").join("all_articles.md")).unwrap()
+}
+
+#[tokio::test]
+async fn korean_text_round_trips_through_every_encoding() {
+    let output_dir = write_archive(
+        &[category_node(1, "분류", &[article_node(10, "제목")])],
+        &[(10, article_json(10, "분류", "제목", "한글 본문"))],
+    );
+    let expected = "```[제목]```\\\n한글 본문\n\n\n\n";
+
+    let utf8 = export(output_dir.path(), "utf8").await;
+    assert_eq!(utf8, expected.as_bytes());
+
+    let utf8_bom = export(output_dir.path(), "utf8-bom").await;
+    let (decoded, had_errors) = encoding_rs::UTF_8.decode_with_bom_removal(&utf8_bom);
+    assert!(utf8_bom.starts_with(b"\xEF\xBB\xBF"));
+    assert!(!had_errors);
+    assert_eq!(decoded, expected);
+
+    let euc_kr = export(output_dir.path(), "euc-kr").await;
+    let (decoded, _, had_errors) = encoding_rs::EUC_KR.decode(&euc_kr);
+    assert_ne!(euc_kr, expected.as_bytes());
+    assert!(!had_errors);
+    assert_eq!(decoded, expected);
+}
+
+#[tokio::test]
+async fn json_exports_stay_utf8() {
+    let output_dir = write_archive(
+        &[category_node(1, "분류", &[article_node(10, "제목")])],
+        &[(10, article_json(10, "분류", "제목", "한글 본문"))],
+    );
+
+    for encoding in ["utf8-bom", "euc-kr"] {
+        let args = Arguments::parse_from([
+            "dfu_crawler".to_string(),
+            "--use-local".to_string(),
+            "--output-dir".to_string(),
+            output_dir.path().display().to_string(),
+            "--output-format".to_string(),
+            "md,json".to_string(),
+            "--output-encoding".to_string(),
+            encoding.to_string(),
+        ]);
+        dfu_crawler::run(args).await.unwrap();
+
+        let final_dir = output_dir.path().join("final");
+        for file_name in ["all_articles.json", "articles_index.json"] {
+            let json = std::fs::read(final_dir.join(file_name)).unwrap();
+            let text = std::str::from_utf8(&json).unwrap();
+            assert!(!text.starts_with('\u{feff}'), "{} {}", encoding, file_name);
+            serde_json::from_str::<serde_json::Value>(text).unwrap();
+        }
+
+        // The markdown next to them is still re-encoded
+        let markdown = std::fs::read(final_dir.join("all_articles.md")).unwrap();
+        match encoding {
+            "utf8-bom" => assert!(markdown.starts_with(b"\xEF\xBB\xBF")),
+            _ => assert!(std::str::from_utf8(&markdown).is_err()),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+#[tokio::test]
+async fn backups_within_the_same_second_are_all_kept() {
+    let output_dir = write_archive(
+        &[category_node(1, "분류", &[article_node(10, "제목")])],
+        &[(10, article_json(10, "분류", "제목", "본문"))],
+    );
+
+    // Quick enough that at least two of the backups share a timestamp
+    for _ in 0..3 {
+        let args = Arguments::parse_from([
+            "dfu_crawler".to_string(),
+            "--use-local".to_string(),
+            "--output-dir".to_string(),
+            output_dir.path().display().to_string(),
+            "--overwrite-policy".to_string(),
+            "backup".to_string(),
+        ]);
+        dfu_crawler::run(args).await.unwrap();
+    }
+
+    let backups = std::fs::read_dir(output_dir.path().join("final"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("all_articles.") && name != "all_articles.md")
+        .count();
+    assert_eq!(backups, 2);
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use std::collections::HashSet;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+#[tokio::test]
+async fn post_process_reports_what_it_wrote() {
+    let output_dir = write_archive(
+        &[
+            category_node(1, "분류", &[article_node(10, "제목")]),
+            category_node(2, "제외", &[article_node(20, "빠짐")]),
+        ],
+        &[
+            (10, article_json(10, "분류", "제목", "본문")),
+            (20, article_json(20, "제외", "빠짐", "본문 2")),
+        ],
+    );
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+    ]);
+
+    let data = dfu_crawler::read_from_local(&args).await.unwrap();
+    let summary = dfu_crawler::post_process(&args, &data, &["제외".to_string()], &HashSet::new())
+        .await
+        .unwrap();
+
+    assert_eq!(summary.articles_written, 1);
+    assert_eq!(summary.excluded, 1);
+    assert_eq!(summary.skipped_missing_lang, 0);
+
+    let final_dir = output_dir.path().join("final");
+    let all_articles = final_dir.join("all_articles.md");
+    assert!(summary.files.contains(&all_articles));
+    assert!(summary.files_skipped.is_empty());
+
+    let bytes_on_disk: u64 = summary
+        .files
+        .iter()
+        .map(|file| std::fs::metadata(file).unwrap().len())
+        .sum();
+    assert_eq!(summary.bytes_written, bytes_on_disk);
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+use tempfile::TempDir;
+
+// The listing now only has article 10, the cache still holds 10 and 20 (with its validators)
+fn setup() -> (TempDir, String) {
+    let base_url = common::serve(|request_line| {
+        let body = match request_line.contains("categories.json") {
+            true => format!(
+                r#"{{"code": "0", "message": "ok", "data": [{}]}}"#,
+                category_node(1, "분류", &[article_node(10, "남은 글")])
+            ),
+            false => String::new(),
+        };
+        ("200 OK", body)
+    });
+
+    let output_dir = write_archive(
+        &[],
+        &[
+            (10, article_json(10, "분류", "남은 글", "본문 1")),
+            (20, article_json(20, "분류", "지운 글", "본문 2")),
+        ],
+    );
+    std::fs::write(
+        output_dir.path().join("articles").join("20.json.http"),
+        "{}",
+    )
+    .unwrap();
+
+    (output_dir, base_url)
+}
+
+async fn prune(output_dir: &TempDir, base_url: &str, extra_args: &[&str]) {
+    let mut args = vec![
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "prune".to_string(),
+    ];
+    args.extend(extra_args.iter().map(|arg| arg.to_string()));
+
+    dfu_crawler::run(Arguments::parse_from(args)).await.unwrap();
+}
+
+fn cached_files(output_dir: &TempDir) -> Vec<String> {
+    let mut files = std::fs::read_dir(output_dir.path().join("articles"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+#[tokio::test]
+async fn prune_removes_articles_no_longer_listed() {
+    let (output_dir, base_url) = setup();
+
+    prune(&output_dir, &base_url, &[]).await;
+
+    assert_eq!(cached_files(&output_dir), ["10.json"]);
+}
+
+#[tokio::test]
+async fn dry_run_leaves_the_cache_alone() {
+    let (output_dir, base_url) = setup();
+
+    prune(&output_dir, &base_url, &["--dry-run"]).await;
+
+    assert_eq!(
+        cached_files(&output_dir),
+        ["10.json", "20.json", "20.json.http"]
+    );
+}
+
+#[tokio::test]
+async fn a_listing_without_articles_prunes_nothing() {
+    let base_url = common::serve(|_| {
+        let body = format!(
+            r#"{{"code": "0", "message": "ok", "data": [{}]}}"#,
+            category_node(1, "빈 분류", &[])
+        );
+        ("200 OK", body)
+    });
+    let output_dir = write_archive(&[], &[(10, article_json(10, "분류", "글", "본문"))]);
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "prune".to_string(),
+    ]);
+    let error = dfu_crawler::run(args).await.unwrap_err();
+
+    assert!(error.to_string().contains("refusing to prune"), "{}", error);
+    assert_eq!(cached_files(&output_dir), ["10.json"]);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+fn categories() -> String {
+    format!(
+        "{{\"code\": \"0\", \"message\": \"ok\", \"data\": [{}, {}]}}",
+        category_node(1, "첫 분류", &[article_node(101, "첫째")]),
+        category_node(2, "둘째 분류", &[article_node(201, "둘째")])
+    )
+}
+
+// Plain HTTP, noting every request path: the listing for categories.json, article 201 otherwise
+fn serve(listener: TcpListener, requests: Arc<Mutex<Vec<String>>>) {
+    for stream in listener.incoming() {
+        let mut stream = stream.unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            if header == "\r\n" || header.is_empty() {
+                break;
+            }
+        }
+        requests.lock().unwrap().push(request_line.clone());
+
+        let body = if request_line.contains("categories.json") {
+            categories()
+        } else {
+            article_json(201, "둘째 분류", "둘째", "본문 2")
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn resume_skips_finished_categories() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(vec![]));
+    let server_requests = requests.clone();
+    thread::spawn(move || serve(listener, server_requests));
+
+    // What an interrupted crawl leaves behind once the first category is done
+    let output_dir = write_archive(
+        &[],
+        &[(101, article_json(101, "첫 분류", "첫째", "본문 1"))],
+    );
+    std::fs::remove_dir_all(output_dir.path().join("category")).unwrap();
+    let checkpoint = output_dir.path().join("checkpoint.json");
+    std::fs::write(&checkpoint, r#"{"last_article_id": 101}"#).unwrap();
+    std::fs::write(
+        output_dir.path().join("checkpoint.categories.jsonl"),
+        // The second line was cut short by the interruption
+        "{\"id\": 1, \"category_names\": [\"첫 분류\"], \"article_ids\": [101]}\n{\"id\": 2, \"categ",
+    )
+    .unwrap();
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "--rps".to_string(),
+        "100".to_string(),
+        "--resume-from".to_string(),
+        checkpoint.display().to_string(),
+        "-y".to_string(),
+    ]);
+
+    dfu_crawler::run(args).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert!(requests
+        .iter()
+        .any(|request| request.contains("/story/201")));
+    assert!(!requests.iter().any(|request| request.contains("101")));
+
+    let final_dir = output_dir.path().join("final");
+    let articles = std::fs::read_to_string(final_dir.join("all_articles.md")).unwrap();
+    assert_eq!(
+        articles,
+        "```[첫째]```\\\n본문 1\n\n\n\n\n```[둘째]```\\\n본문 2\n\n\n\n"
+    );
+    let category_names = std::fs::read_to_string(final_dir.join("category_names.txt")).unwrap();
+    assert_eq!(category_names, "첫 분류\n둘째 분류");
+
+    // A completed crawl leaves nothing to resume
+    assert!(!checkpoint.exists());
+    assert!(!output_dir
+        .path()
+        .join("checkpoint.categories.jsonl")
+        .exists());
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node};
+use dfu_crawler::Arguments;
+
+#[tokio::test]
+async fn only_requests_whose_retries_ran_out_failed_permanently() {
+    // 101 loads, 102 is missing and never retried, 103 keeps failing until the retries run out
+    let base_url = common::serve(|request_line| {
+        if request_line.contains("categories.json") {
+            let categories = category_node(
+                1,
+                "분류",
+                &[
+                    article_node(101, "첫째"),
+                    article_node(102, "없음"),
+                    article_node(103, "고장"),
+                ],
+            );
+            let body = format!(
+                r#"{{"code": "0", "message": "ok", "data": [{}]}}"#,
+                categories
+            );
+            ("200 OK", body)
+        } else if request_line.contains("102") {
+            ("404 Not Found", String::new())
+        } else if request_line.contains("103") {
+            ("503 Service Unavailable", String::new())
+        } else {
+            ("200 OK", article_json(101, "분류", "첫째", "본문"))
+        }
+    });
+    let output_dir = common::output_dir();
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "--rps".to_string(),
+        "100".to_string(),
+        "--retries".to_string(),
+        "2".to_string(),
+        "--max-backoff-secs".to_string(),
+        "0".to_string(),
+        "-y".to_string(),
+    ]);
+    dfu_crawler::run(args).await.unwrap_err();
+
+    let stats: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(output_dir.path().join("final").join("crawl_stats.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stats["retries"], 2);
+    assert_eq!(stats["succeeded_after_retry"], 0);
+    assert_eq!(stats["failed_permanently"], 1);
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use std::{
+    fs::File,
+    time::{Duration, SystemTime},
+};
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+#[test]
+fn since_needs_use_local() {
+    let error = Arguments::try_parse_from(["dfu_crawler", "--since", "2024-05-01"]).unwrap_err();
+    assert!(error.to_string().contains("--use-local"), "{}", error);
+}
+
+#[tokio::test]
+async fn since_keeps_the_articles_cached_after_it() {
+    let output_dir = write_archive(
+        &[category_node(
+            1,
+            "분류",
+            &[article_node(10, "옛 글"), article_node(20, "새 글")],
+        )],
+        &[
+            (10, article_json(10, "분류", "옛 글", "본문 1")),
+            (20, article_json(20, "분류", "새 글", "본문 2")),
+        ],
+    );
+    let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_577_836_800); // 2020-01-01
+    File::options()
+        .write(true)
+        .open(output_dir.path().join("articles").join("10.json"))
+        .unwrap()
+        .set_modified(old)
+        .unwrap();
+
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--since".to_string(),
+        "2024-05-01".to_string(),
+    ]);
+    dfu_crawler::run(args).await.unwrap();
+
+    let exported =
+        std::fs::read_to_string(output_dir.path().join("final").join("all_articles.md")).unwrap();
+    assert_eq!(exported, "```[새 글]```\\\n본문 2\n\n\n\n");
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use std::path::Path;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+fn english_article(id: i32, title: &str) -> String {
+    article_json(id, "분류", "제목", "본문")
+        .replace(
+            r#""titles": {"KR": "제목"}"#,
+            &format!(r#""titles": {{"KR": "제목", "EN": "{}"}}"#, title),
+        )
+        .replace(
+            r#""contents": {"KR": "본문"}"#,
+            r#""contents": {"KR": "본문", "EN": "Body"}"#,
+        )
+}
+
+async fn export(output_dir: &Path, lang: &str) -> Vec<String> {
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.display().to_string(),
+        "--lang".to_string(),
+        lang.to_string(),
+        "--title-case".to_string(),
+    ]);
+    dfu_crawler::run(args).await.unwrap();
+
+    std::fs::read_to_string(output_dir.join("final").join("all_articles.md"))
+        .unwrap()
+        .lines()
+        .filter(|line| line.starts_with("```["))
+        .map(str::to_string)
+        .collect()
+}
+
+#[tokio::test]
+async fn english_titles_are_title_cased() {
+    let titles = [
+        "THE LORD OF THE RINGS",
+        "a tale of two cities: the end",
+        "iPhone tips and tricks",
+        "where it comes from",
+    ];
+    let output_dir = write_archive(
+        &[category_node(
+            1,
+            "분류",
+            &(10..14)
+                .map(|id| article_node(id, "제목"))
+                .collect::<Vec<_>>(),
+        )],
+        &(10..14)
+            .zip(titles)
+            .map(|(id, title)| (id, english_article(id, title)))
+            .collect::<Vec<_>>(),
+    );
+
+    assert_eq!(
+        export(output_dir.path(), "en").await,
+        [
+            "```[The Lord of the Rings]```\\",
+            "```[A Tale of Two Cities: The End]```\\",
+            "```[iPhone Tips and Tricks]```\\",
+            "```[Where It Comes From]```\\",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn korean_titles_are_left_alone() {
+    let output_dir = write_archive(
+        &[category_node(1, "분류", &[article_node(10, "제목")])],
+        &[(10, english_article(10, "the end"))],
+    );
+
+    assert_eq!(export(output_dir.path(), "kr").await, ["```[제목]```\\"]);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use clap::Parser;
+use common::{article_json, article_node, category_node};
+use dfu_crawler::Arguments;
+
+#[tokio::test]
+async fn a_transient_error_is_retried_before_a_link_counts_as_broken() {
+    let flaky_checks = Arc::new(AtomicUsize::new(0));
+    let server_flaky_checks = flaky_checks.clone();
+    let base_url = common::serve(move |request_line| {
+        if request_line.starts_with("HEAD /story/101") {
+            match server_flaky_checks.fetch_add(1, Ordering::SeqCst) {
+                0 => ("503 Service Unavailable", String::new()),
+                _ => ("200 OK", String::new()),
+            }
+        } else if request_line.starts_with("HEAD /story/102") {
+            ("404 Not Found", String::new())
+        } else if request_line.contains("categories.json") {
+            let categories = category_node(
+                1,
+                "분류",
+                &[article_node(101, "첫째"), article_node(102, "둘째")],
+            );
+            let body = format!(
+                r#"{{"code": "0", "message": "ok", "data": [{}]}}"#,
+                categories
+            );
+            ("200 OK", body)
+        } else if request_line.contains("/story/101") {
+            ("200 OK", article_json(101, "분류", "첫째", "본문 1"))
+        } else {
+            ("200 OK", article_json(102, "분류", "둘째", "본문 2"))
+        }
+    });
+
+    let output_dir = common::output_dir();
+    let args = Arguments::parse_from([
+        "dfu_crawler".to_string(),
+        "--output-dir".to_string(),
+        output_dir.path().display().to_string(),
+        "--categories-url".to_string(),
+        format!("{}/categories.json", base_url),
+        "--article-base-url".to_string(),
+        format!("{}/story", base_url),
+        "--rps".to_string(),
+        "100".to_string(),
+        "--validate-links".to_string(),
+        "--max-backoff-secs".to_string(),
+        "0".to_string(),
+        "-y".to_string(),
+    ]);
+    dfu_crawler::run(args).await.unwrap();
+
+    assert_eq!(flaky_checks.load(Ordering::SeqCst), 2);
+
+    let final_dir = output_dir.path().join("final");
+    let report = std::fs::read_dir(&final_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.file_stem().is_some_and(|stem| stem == "broken_links"))
+        .unwrap();
+    let broken = std::fs::read_to_string(report).unwrap();
+    assert!(broken.contains("/story/102"), "{}", broken);
+    assert!(!broken.contains("/story/101"), "{}", broken);
+}
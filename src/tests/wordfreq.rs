@@ -0,0 +1,79 @@
+mod common;
+
+use std::path::Path;
+
+use clap::Parser;
+use common::{article_json, article_node, category_node, write_archive};
+use dfu_crawler::Arguments;
+
+async fn wordfreq(output_dir: &Path, extra_args: &[&str]) -> String {
+    let mut args = vec![
+        "dfu_crawler".to_string(),
+        "--use-local".to_string(),
+        "--output-dir".to_string(),
+        output_dir.display().to_string(),
+    ];
+    args.extend(extra_args.iter().map(|arg| arg.to_string()));
+
+    dfu_crawler::run(Arguments::parse_from(args)).await.unwrap();
+
+    let lang = match extra_args.contains(&"en") {
+        true => "en",
+        false => "kr",
+    };
+    std::fs::read_to_string(
+        output_dir
+            .join("final")
+            .join(format!("wordfreq_{}.csv", lang)),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn korean_is_counted_in_character_bigrams() {
+    let output_dir = write_archive(
+        &[category_node(
+            1,
+            "분류",
+            &[article_node(10, "첫째"), article_node(20, "둘째")],
+        )],
+        &[
+            (10, article_json(10, "분류", "첫째", "가나다 DFU")),
+            (20, article_json(20, "분류", "둘째", "가나, 7")),
+        ],
+    );
+
+    let counts = wordfreq(output_dir.path(), &["wordfreq"]).await;
+    assert_eq!(counts, "token,count\n가나,2\n7,1\ndfu,1\n나다,1\n");
+}
+
+#[tokio::test]
+async fn english_is_counted_in_words_without_the_stopwords() {
+    let output_dir = write_archive(
+        &[category_node(1, "분류", &[article_node(10, "제목")])],
+        &[(
+            10,
+            article_json(10, "분류", "제목", "본문").replace(
+                r#""contents": {"KR": "본문"}"#,
+                r#""contents": {"KR": "본문", "EN": "The cat saw the other Cat"}"#,
+            ),
+        )],
+    );
+    let stopwords = output_dir.path().join("stopwords.txt");
+    std::fs::write(&stopwords, "# articles\nthe\nsaw # verbs too\n").unwrap();
+
+    let counts = wordfreq(
+        output_dir.path(),
+        &[
+            "--lang",
+            "en",
+            "wordfreq",
+            "--top",
+            "1",
+            "--stopwords",
+            &stopwords.display().to_string(),
+        ],
+    )
+    .await;
+    assert_eq!(counts, "token,count\ncat,2\n");
+}